@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: MIT
 
+use crate::{icons, style};
 use clap::{
     ArgAction, ArgGroup, Command, CommandFactory, Parser, ValueEnum, ValueHint,
     builder::PossibleValue,
@@ -82,6 +83,66 @@ impl ValueEnum for CompletionShell {
     }
 }
 
+#[derive(Clone, Copy)]
+pub enum OverviewBy {
+    Ext,
+    Dir,
+}
+
+impl ValueEnum for OverviewBy {
+    fn value_variants<'a>() -> &'a [Self] {
+        static VARIANTS: [OverviewBy; 2] = [OverviewBy::Ext, OverviewBy::Dir];
+        &VARIANTS
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let name = match self {
+            OverviewBy::Ext => "ext",
+            OverviewBy::Dir => "dir",
+        };
+        Some(PossibleValue::new(name))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum OutputFormat {
+    Json,
+    TreeJson,
+    RgJson,
+    Vimgrep,
+    Sarif,
+    Html,
+    Markdown,
+}
+
+impl ValueEnum for OutputFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        static VARIANTS: [OutputFormat; 7] = [
+            OutputFormat::Json,
+            OutputFormat::TreeJson,
+            OutputFormat::RgJson,
+            OutputFormat::Vimgrep,
+            OutputFormat::Sarif,
+            OutputFormat::Html,
+            OutputFormat::Markdown,
+        ];
+        &VARIANTS
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let name = match self {
+            OutputFormat::Json => "json",
+            OutputFormat::TreeJson => "tree-json",
+            OutputFormat::RgJson => "rg-json",
+            OutputFormat::Vimgrep => "vimgrep",
+            OutputFormat::Sarif => "sarif",
+            OutputFormat::Html => "html",
+            OutputFormat::Markdown => "markdown",
+        };
+        Some(PossibleValue::new(name))
+    }
+}
+
 impl CompletionShell {
     pub fn generate(&self, cmd: &mut Command, bin: &str, out: &mut dyn std::io::Write) {
         match self {
@@ -115,6 +176,134 @@ fn positive(s: &str) -> Result<usize, String> {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Placeholder {
+    Path,
+    Relpath,
+    Line,
+    Col,
+    Text,
+    Match,
+    PatternId,
+    Depth,
+    Count,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum TemplatePart {
+    Text(String),
+    Field(Placeholder),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Template(pub Vec<TemplatePart>);
+
+fn template(s: &str) -> Result<Template, String> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('t') => text.push('\t'),
+                Some('0') => text.push('\0'),
+                Some('\\') => text.push('\\'),
+                Some(c) => return Err(format!("unknown escape `\\{c}`")),
+                None => return Err("trailing `\\`".to_string()),
+            },
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let rest = chars.as_str();
+                let end = rest
+                    .find('}')
+                    .ok_or_else(|| "unclosed `{`, use `{{` for a literal brace".to_string())?;
+                let field = match &rest[..end] {
+                    "path" => Placeholder::Path,
+                    "relpath" => Placeholder::Relpath,
+                    "line" => Placeholder::Line,
+                    "col" => Placeholder::Col,
+                    "text" => Placeholder::Text,
+                    "match" => Placeholder::Match,
+                    "pattern_id" => Placeholder::PatternId,
+                    "depth" => Placeholder::Depth,
+                    "count" => Placeholder::Count,
+                    name => return Err(format!("unknown placeholder `{{{name}}}`")),
+                };
+                chars = rest[end + 1..].chars();
+                if !text.is_empty() {
+                    parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                }
+                parts.push(TemplatePart::Field(field));
+            }
+            '}' => return Err("unmatched `}`, use `}}` for a literal brace".to_string()),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(TemplatePart::Text(text));
+    }
+    Ok(Template(parts))
+}
+
+pub const HYPERLINK_PRESETS: &[(&str, &str)] = &[
+    ("default", "file://{host}{path}"),
+    ("file", "file://{host}{path}"),
+    ("vscode", "vscode://file{path}:{line}"),
+    ("vscode-insiders", "vscode-insiders://file{path}:{line}"),
+    ("vscodium", "vscodium://file{path}:{line}"),
+    ("cursor", "cursor://file{path}:{line}"),
+    ("macvim", "mvim://open?url=file://{path}&line={line}"),
+    ("textmate", "txmt://open?url=file://{path}&line={line}"),
+    ("kitty", "file://{host}{path}#{line}"),
+];
+
+fn icon_override(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, glyph)) if icons::valid_key(key) && !glyph.is_empty() => {
+            Ok((key.to_string(), glyph.to_string()))
+        }
+        _ => Err(format!(
+            "`{s}` is not in the form key=glyph with key dir, file, dir:NAME, name:NAME or ext:EXT"
+        )),
+    }
+}
+
+fn hyperlink_format(s: &str) -> Result<String, String> {
+    if let Some((_, format)) = HYPERLINK_PRESETS.iter().find(|(name, _)| *name == s) {
+        return Ok(format.to_string());
+    }
+    let mut rest = s;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed `{{` in `{s}`"))?;
+        match &rest[start + 1..start + end] {
+            "host" | "path" | "line" => {}
+            name => return Err(format!("unknown placeholder `{{{name}}}`")),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    if !s.contains("{path}") {
+        return Err(format!(
+            "`{s}` is not one of {} and has no {{path}}",
+            HYPERLINK_PRESETS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    Ok(s.to_string())
+}
+
 #[derive(Clone, Copy)]
 pub enum Color {
     Black,
@@ -148,6 +337,48 @@ fn color_validation_error(cmd: &Command, kind: &str, value: &str) -> Error {
     err
 }
 
+pub enum ColorError {
+    Invalid(&'static str, String),
+    Unknown(String),
+}
+
+pub fn parse_color(value: &str) -> Result<Color, ColorError> {
+    let s = value.trim().to_lowercase();
+    let color = match s.as_str() {
+        "black" => Color::Black,
+        "white" => Color::White,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "grey" | "gray" => Color::Grey,
+        _ if s.starts_with("rgb(") && s.ends_with(')') => {
+            let inner = &s[4..s.len() - 1];
+            let nums: Vec<_> = inner
+                .split('.')
+                .map(|x| x.trim().parse::<u8>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| ColorError::Invalid("rgb", inner.to_string()))?;
+            if nums.len() == 3 {
+                Color::Rgb(nums[0], nums[1], nums[2])
+            } else {
+                return Err(ColorError::Invalid("rgb", inner.to_string()));
+            }
+        }
+        _ if s.starts_with("ansi(") && s.ends_with(')') => {
+            let inner = &s[5..s.len() - 1];
+            let v = inner
+                .parse::<u8>()
+                .map_err(|_| ColorError::Invalid("ansi", inner.to_string()))?;
+            Color::Ansi(v)
+        }
+        _ => return Err(ColorError::Unknown(s)),
+    };
+    Ok(color)
+}
+
 #[derive(Clone)]
 pub struct ColorParser;
 
@@ -160,50 +391,18 @@ impl clap::builder::TypedValueParser for ColorParser {
         arg: Option<&clap::Arg>,
         value: &OsStr,
     ) -> Result<Self::Value, Error> {
-        let s = value.to_string_lossy().trim().to_lowercase();
-
-        let color = match s.as_str() {
-            "black" => Color::Black,
-            "white" => Color::White,
-            "red" => Color::Red,
-            "green" => Color::Green,
-            "yellow" => Color::Yellow,
-            "blue" => Color::Blue,
-            "magenta" => Color::Magenta,
-            "cyan" => Color::Cyan,
-            "grey" | "gray" => Color::Grey,
-            _ if s.starts_with("rgb(") && s.ends_with(')') => {
-                let inner = &s[4..s.len() - 1];
-                let nums: Vec<_> = inner
-                    .split('.')
-                    .map(|x| x.trim().parse::<u8>())
-                    .collect::<Result<_, _>>()
-                    .map_err(|_| color_validation_error(cmd, "rgb", inner))?;
-                if nums.len() == 3 {
-                    Color::Rgb(nums[0], nums[1], nums[2])
-                } else {
-                    return Err(color_validation_error(cmd, "rgb", inner));
-                }
-            }
-            _ if s.starts_with("ansi(") && s.ends_with(')') => {
-                let inner = &s[5..s.len() - 1];
-                let v = inner
-                    .parse::<u8>()
-                    .map_err(|_| color_validation_error(cmd, "ansi", inner))?;
-                Color::Ansi(v)
-            }
-            _ => {
+        parse_color(&value.to_string_lossy()).map_err(|e| match e {
+            ColorError::Invalid(kind, inner) => color_validation_error(cmd, kind, &inner),
+            ColorError::Unknown(s) => {
                 let mut err = Error::new(ErrorKind::InvalidValue).with_cmd(cmd);
                 err.insert(
                     ContextKind::InvalidArg,
                     ContextValue::String(arg.unwrap().to_string()),
                 );
-                err.insert(ContextKind::InvalidValue, ContextValue::String(s.clone()));
-                return Err(err);
+                err.insert(ContextKind::InvalidValue, ContextValue::String(s));
+                err
             }
-        };
-
-        Ok(color)
+        })
     }
 }
 
@@ -299,11 +498,35 @@ pub const PATH_POSITIONAL: &str = "positional_path";
 pub const PATH: &str = "path";
 pub const NO_COLORS: &str = "no_color";
 pub const COUNT: &str = "count";
+pub const TOTALS: &str = "totals";
+pub const LONG: &str = "long";
+pub const PERMISSIONS: &str = "permissions";
 pub const HIDDEN: &str = "hidden";
 pub const LINE_NUMBER: &str = "line_number";
 pub const SELECT: &str = "select";
 pub const MENU: &str = "menu";
+pub const JSON: &str = "json";
+pub const OUTPUT_FORMAT: &str = "output_format";
+pub const RULE_NAME: &str = "rule_name";
+pub const VIMGREP: &str = "vimgrep";
+pub const FORMAT: &str = "format";
+pub const FORMAT_LINE: &str = "format_line";
+pub const FORMAT_FILE: &str = "format_file";
+pub const NULL: &str = "null";
+pub const HYPERLINK_FORMAT: &str = "hyperlink_format";
+pub const LS_COLORS: &str = "ls_colors";
+pub const ICONS: &str = "icons";
+pub const ICON: &str = "icon";
+pub const QUICKFIX_FILE: &str = "quickfix_file";
 pub const FILES: &str = "files";
+pub const NAME_REGEXP: &str = "name_regexp";
+pub const DIRS: &str = "dirs";
+pub const COMPACT_DIRS: &str = "compact_dirs";
+pub const FLAT: &str = "flat";
+pub const WRAP: &str = "wrap";
+pub const SYNTAX: &str = "syntax";
+pub const SYNTAX_THEME: &str = "syntax_theme";
+pub const THEME: &str = "theme";
 pub const MAX_DEPTH: &str = "max_depth";
 pub const CHAR_VERTICAL: &str = "char_vertical";
 pub const CHAR_HORIZONTAL: &str = "char_horizontal";
@@ -337,10 +560,14 @@ pub const KEY_FOLD: &str = "key_fold";
 pub const KEY_FILTER: &str = "key_filter";
 pub const KEY_SEARCH: &str = "key_search";
 pub const KEY_SUBMIT_SEARCH: &str = "key_submit_search";
+pub const KEY_WRAP: &str = "key_wrap";
+pub const KEY_SYNTAX: &str = "key_syntax";
 pub const OVERVIEW: &str = "overview";
 pub const OVERVIEW_ONLY: &str = "overview_only";
+pub const OVERVIEW_BY: &str = "overview_by";
 pub const AUTO_OPEN: &str = "auto_open";
 pub const BRANCH_LEN: &str = "branch_len";
+pub const TAB_WIDTH: &str = "tab_width";
 pub const LINKS: &str = "links";
 pub const TRIM_LEFT: &str = "trim";
 pub const NO_IGNORE: &str = "no_ignore";
@@ -354,6 +581,7 @@ pub const BEFORE_CONTEXT: &str = "before_context";
 pub const AFTER_CONTEXT: &str = "after_context";
 pub const CONTEXT: &str = "context";
 pub const PCRE2: &str = "pcre2";
+pub const TALLY: &str = "tally";
 
 pub const DEFAULT_OPTS_ENV_NAME: &str = "TREEGREP_DEFAULT_OPTS";
 
@@ -364,7 +592,16 @@ pub const REPEAT_STORED: &[&str] = &[
     HIDDEN,
     LINE_NUMBER,
     FILES,
+    NAME_REGEXP,
+    DIRS,
+    COMPACT_DIRS,
+    FLAT,
+    WRAP,
+    SYNTAX,
     COUNT,
+    TOTALS,
+    LONG,
+    PERMISSIONS,
     LINKS,
     TRIM_LEFT,
     NO_IGNORE,
@@ -374,8 +611,10 @@ pub const REPEAT_STORED: &[&str] = &[
     AFTER_CONTEXT,
     OVERVIEW,
     OVERVIEW_ONLY,
+    OVERVIEW_BY,
     LONG_BRANCHES_EACH,
     PCRE2,
+    TALLY,
 ];
 
 pub const HELP_TEMPLATE: &str = concat!(
@@ -395,9 +634,10 @@ pub const HELP_TEMPLATE: &str = concat!(
     disable_help_flag = true,
     disable_version_flag = true,
     next_help_heading = "options",
-    group(ArgGroup::new("mode").required(true).multiple(true).args([EXPRESSION_POSITIONAL, EXPRESSION, FILES, COMPLETIONS, MENU, REPEAT])),
+    group(ArgGroup::new("mode").required(true).multiple(true).args([EXPRESSION_POSITIONAL, EXPRESSION, FILES, DIRS, COMPLETIONS, MENU, REPEAT])),
     group(ArgGroup::new("expressions").args([EXPRESSION_POSITIONAL, EXPRESSION]).multiple(true)),
     group(ArgGroup::new("paths").args([PATH_POSITIONAL, PATH])),
+    group(ArgGroup::new("overviews").args([OVERVIEW, OVERVIEW_ONLY]).multiple(true)),
 )]
 pub struct Args {
     #[arg(
@@ -442,6 +682,102 @@ pub struct Args {
     #[arg(long, short = 'm', help = "open a search and selection interface")]
     pub menu: bool,
 
+    #[arg(
+        long,
+        conflicts_with_all = [SELECT, MENU, OUTPUT_FORMAT],
+        help = "print results as json lines, one object per event, same as --output-format=json"
+    )]
+    pub json: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = [SELECT, MENU, JSON, OUTPUT_FORMAT],
+        help = "print path:line:column:text for every match with the column in bytes like rg, same as --output-format=vimgrep"
+    )]
+    pub vimgrep: bool,
+
+    #[arg(
+        long,
+        value_name = "",
+        value_parser = template,
+        conflicts_with_all = [SELECT, MENU, JSON, VIMGREP, OUTPUT_FORMAT, FORMAT_LINE],
+        help = "print each match with a template of {path}, {relpath}, {line}, {col}, {text}, {match}, {pattern_id}, {depth} and {count}, where {col} is the 1-based byte column"
+    )]
+    pub format: Option<Template>,
+
+    #[arg(
+        long,
+        value_name = "",
+        value_parser = template,
+        conflicts_with_all = [SELECT, MENU, JSON, VIMGREP, OUTPUT_FORMAT],
+        hide_short_help = true,
+        help = "print each line with a template, like --format with the first match of the line"
+    )]
+    pub format_line: Option<Template>,
+
+    #[arg(
+        long,
+        value_name = "",
+        value_parser = template,
+        conflicts_with_all = [SELECT, MENU, JSON, VIMGREP, OUTPUT_FORMAT],
+        hide_short_help = true,
+        help = "print a header for each file with a template, like --format"
+    )]
+    pub format_file: Option<Template>,
+
+    #[arg(
+        long,
+        short = '0',
+        conflicts_with_all = [SELECT, MENU, JSON, VIMGREP, OUTPUT_FORMAT, FORMAT, FORMAT_LINE, FORMAT_FILE, DIRS],
+        help = "only print the paths of matching files, each followed by a NUL byte"
+    )]
+    pub null: bool,
+
+    #[arg(
+        long,
+        value_name = "",
+        value_parser = hyperlink_format,
+        hide_short_help = true,
+        help = "link paths and lines in the terminal, a template with {host}, {path} and {line} or one of default, file, vscode, vscode-insiders, vscodium, cursor, macvim, textmate, kitty"
+    )]
+    pub hyperlink_format: Option<String>,
+
+    #[arg(
+        long,
+        help = "color paths by type and extension using the LS_COLORS environment variable"
+    )]
+    pub ls_colors: bool,
+
+    #[arg(long, help = "show a Nerd Font icon before each file and directory")]
+    pub icons: bool,
+
+    #[arg(
+        long,
+        value_name = "",
+        value_parser = icon_override,
+        action = ArgAction::Append,
+        hide_short_help = true,
+        help = "override an icon as key=glyph, with key dir, file, dir:NAME, name:NAME or ext:EXT"
+    )]
+    pub icon: Vec<(String, String)>,
+
+    #[arg(
+        long,
+        value_name = "",
+        conflicts_with_all = [SELECT, MENU],
+        help = "print results in a machine readable format instead of a tree, vimgrep columns count bytes and sarif columns count unicode code points"
+    )]
+    pub output_format: Option<OutputFormat>,
+
+    #[arg(
+        long,
+        value_name = "",
+        action = ArgAction::Append,
+        requires = EXPRESSION,
+        help = "name the sarif rule of each -e pattern, in the same order, kept apart from -e so patterns containing = are searched as written"
+    )]
+    pub rule_name: Vec<String>,
+
     #[arg(
         long,
         hide_short_help = true,
@@ -464,6 +800,48 @@ pub struct Args {
     )]
     pub files: bool,
 
+    #[arg(
+        long,
+        short = 'N',
+        requires = "expressions",
+        conflicts_with = TALLY,
+        help = "match expressions against file and directory names instead of contents"
+    )]
+    pub name_regexp: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = [FILES, TALLY],
+        help = "only show directories with the number of files and matches within each, if no expression is given, show all directories"
+    )]
+    pub dirs: bool,
+
+    #[arg(
+        long,
+        help = "merge directories that only contain a single directory into one branch"
+    )]
+    pub compact_dirs: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = [DIRS, TALLY],
+        help = "print path:line:text for each line like grep instead of a tree"
+    )]
+    pub flat: bool,
+
+    #[arg(
+        long,
+        help = "wrap long lines onto continuation rows instead of cutting them"
+    )]
+    pub wrap: bool,
+
+    #[arg(
+        long,
+        hide = !cfg!(feature = "syntax"),
+        help = "color the unmatched text of lines by file type"
+    )]
+    pub syntax: bool,
+
     #[arg(long, short = '.', help = "search hidden files")]
     pub hidden: bool,
 
@@ -477,6 +855,22 @@ pub struct Args {
     )]
     pub count: bool,
 
+    #[arg(
+        long,
+        requires = COUNT,
+        help = "count files, lines and matches recursively for directories, with their share of all matches"
+    )]
+    pub totals: bool,
+
+    #[arg(
+        long,
+        help = "show the size and modification time in UTC of files, like ls -l"
+    )]
+    pub long: bool,
+
+    #[arg(long, requires = LONG, help = "also show the permissions of files")]
+    pub permissions: bool,
+
     #[arg(long, short = 'g', value_name = "", action = ArgAction::Append, help = "rules match .gitignore globs, but ! has inverted meaning, overrides other ignore logic")]
     pub glob: Vec<String>,
 
@@ -489,6 +883,25 @@ pub struct Args {
     #[arg(long, short = 'S', help = "only show the overview, not the results")]
     pub overview_only: bool,
 
+    #[arg(
+        long,
+        value_name = "",
+        requires = "overviews",
+        help = "break the overview down by file extension or top level directory"
+    )]
+    pub overview_by: Option<OverviewBy>,
+
+    #[arg(
+        long,
+        value_name = "",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "0",
+        requires = "expressions",
+        help = "show how often each matched text occurs, optionally of a capture group"
+    )]
+    pub tally: Option<String>,
+
     #[arg(
         long,
         short = 'd',
@@ -569,6 +982,26 @@ pub struct Args {
     )]
     pub branch_len: usize,
 
+    #[arg(
+        long,
+        default_value_t = 4,
+        value_parser = positive,
+        hide_short_help = true,
+        value_name = "",
+        help = "number of columns a tab expands to"
+    )]
+    pub tab_width: usize,
+
+    #[arg(
+        long,
+        default_value = "base16-ocean.dark",
+        hide = !cfg!(feature = "syntax"),
+        hide_short_help = true,
+        value_name = "",
+        help = "bundled theme used by --syntax"
+    )]
+    pub syntax_theme: String,
+
     #[arg(
         long,
         default_value_t = 1,
@@ -629,6 +1062,13 @@ pub struct Args {
     )]
     pub no_alternate_screen: bool,
 
+    #[arg(
+        long,
+        value_name = "",
+        help = "dark, light or the path to a theme file of colors and branch characters"
+    )]
+    pub theme: Option<String>,
+
     #[arg(long, value_parser = ColorParser, value_name = "", hide_short_help = true, help = COLOR_HELP)]
     pub file_color: Option<Color>,
 
@@ -658,7 +1098,7 @@ pub struct Args {
 
     #[arg(
         long,
-        default_value_t = '│',
+        default_value_t = style::CHAR_VERTICAL_DEFAULT,
         hide_short_help = true,
         value_name = "",
         help = "vertical branch character"
@@ -667,7 +1107,7 @@ pub struct Args {
 
     #[arg(
         long,
-        default_value_t = '─',
+        default_value_t = style::CHAR_HORIZONTAL_DEFAULT,
         hide_short_help = true,
         value_name = "",
         help = "horizontal branch character"
@@ -676,7 +1116,7 @@ pub struct Args {
 
     #[arg(
         long,
-        default_value_t = '╭',
+        default_value_t = style::CHAR_TOP_LEFT_DEFAULT,
         hide_short_help = true,
         value_name = "",
         help = "top-left corner character"
@@ -685,7 +1125,7 @@ pub struct Args {
 
     #[arg(
         long,
-        default_value_t = '╮',
+        default_value_t = style::CHAR_TOP_RIGHT_DEFAULT,
         hide_short_help = true,
         value_name = "",
         help = "top-right corner character"
@@ -694,7 +1134,7 @@ pub struct Args {
 
     #[arg(
         long,
-        default_value_t = '╰',
+        default_value_t = style::CHAR_BOTTOM_LEFT_DEFAULT,
         hide_short_help = true,
         value_name = "",
         help = "bottom-left corner character"
@@ -703,7 +1143,7 @@ pub struct Args {
 
     #[arg(
         long,
-        default_value_t = '╯',
+        default_value_t = style::CHAR_BOTTOM_RIGHT_DEFAULT,
         hide_short_help = true,
         value_name = "",
         help = "bottom-right corner character"
@@ -712,7 +1152,7 @@ pub struct Args {
 
     #[arg(
         long,
-        default_value_t = '├',
+        default_value_t = style::CHAR_TEE_DEFAULT,
         hide_short_help = true,
         value_name = "",
         help = "tee branch character"
@@ -824,9 +1264,18 @@ pub struct Args {
     #[arg(long, value_parser = KeyCodeParser, default_values = ["enter"], hide_short_help = true, value_name = "", help = "submit search query")]
     pub key_submit_search: Vec<KeyCode>,
 
+    #[arg(long, value_parser = KeyCodeParser, default_values = ["w"], hide_short_help = true, value_name = "", help = "toggle wrapping long lines")]
+    pub key_wrap: Vec<KeyCode>,
+
+    #[arg(long, value_parser = KeyCodeParser, default_values = ["y"], hide = !cfg!(feature = "syntax"), hide_short_help = true, value_name = "", help = "toggle syntax highlighting")]
+    pub key_syntax: Vec<KeyCode>,
+
     #[arg(long, value_parser = value_parser!(PathBuf), value_name = "", hide_short_help = true, value_hint = ValueHint::AnyPath, help = "file to write selection to")]
     pub selection_file: Option<PathBuf>,
 
+    #[arg(long, value_parser = value_parser!(PathBuf), value_name = "", hide_short_help = true, value_hint = ValueHint::AnyPath, help = "file to write every match to in vimgrep format, with byte columns")]
+    pub quickfix_file: Option<PathBuf>,
+
     #[arg(long, value_parser = value_parser!(PathBuf), value_name = "", hide_short_help = true, value_hint = ValueHint::AnyPath, help = "file to save and replay regexp searches")]
     pub repeat_file: Option<PathBuf>,
 
@@ -903,4 +1352,47 @@ mod tests {
         assert!(parse_key("toolong").is_err());
         assert!(parse_key("").is_err());
     }
+
+    #[test]
+    fn test_template() {
+        assert_eq!(
+            template(r"{path}:{line}\t{{x}}").unwrap(),
+            Template(vec![
+                TemplatePart::Field(Placeholder::Path),
+                TemplatePart::Text(":".to_string()),
+                TemplatePart::Field(Placeholder::Line),
+                TemplatePart::Text("\t{x}".to_string()),
+            ])
+        );
+        assert!(template("{nope}").is_err());
+        assert!(template("{path").is_err());
+        assert!(template("}").is_err());
+        assert!(template(r"\q").is_err());
+    }
+
+    #[test]
+    fn test_icon_override() {
+        assert_eq!(
+            icon_override("ext:rs=R").unwrap(),
+            ("ext:rs".to_string(), "R".to_string())
+        );
+        assert!(icon_override("rs=R").is_err());
+        assert!(icon_override("ext:rs").is_err());
+        assert!(icon_override("=R").is_err());
+    }
+
+    #[test]
+    fn test_hyperlink_format() {
+        assert_eq!(
+            hyperlink_format("vscode").unwrap(),
+            "vscode://file{path}:{line}"
+        );
+        assert_eq!(
+            hyperlink_format("idea://open?file={path}&line={line}").unwrap(),
+            "idea://open?file={path}&line={line}"
+        );
+        assert!(hyperlink_format("unknown").is_err());
+        assert!(hyperlink_format("x://{path}{col}").is_err());
+        assert!(hyperlink_format("x://{path").is_err());
+    }
 }
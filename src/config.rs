@@ -1,15 +1,23 @@
 // SPDX-License-Identifier: MIT
 
 use crate::{
-    args::{self, Args, CompletionShell, OpenStrategy, REPEAT_FILE, generate_command},
+    args::{
+        self, Args, CompletionShell, OpenStrategy, OutputFormat, OverviewBy, REPEAT_FILE, Template,
+        generate_command,
+    },
     errors::Message,
-    mes, style,
+    hyperlink::Hyperlink,
+    icons::Icons,
+    ls_colors::LsColors,
+    mes, style, syntax,
+    theme::{self, Theme},
 };
-use clap::{ArgMatches, Error, FromArgMatches};
+use clap::{ArgMatches, Error, FromArgMatches, ValueEnum};
 use crossterm::{event::KeyCode, style::Color};
 use std::{
     collections::HashSet,
     ffi::OsString,
+    io::IsTerminal,
     path::{Component, Path, PathBuf},
     sync::{Arc, OnceLock},
 };
@@ -46,6 +54,8 @@ pub struct KeyBindings {
     pub filter: Vec<KeyCode>,
     pub search: Vec<KeyCode>,
     pub submit_search: Vec<KeyCode>,
+    pub wrap: Vec<KeyCode>,
+    pub syntax: Vec<KeyCode>,
 }
 
 #[derive(Clone)]
@@ -83,7 +93,7 @@ pub struct Colors {
 }
 
 impl args::Color {
-    fn get(&self) -> Color {
+    pub fn get(&self) -> Color {
         match *self {
             args::Color::Black => Color::Black,
             args::Color::White => Color::White,
@@ -100,6 +110,19 @@ impl args::Color {
     }
 }
 
+#[derive(Clone, Default)]
+pub struct Templates {
+    pub each_match: Option<Template>,
+    pub line: Option<Template>,
+    pub file: Option<Template>,
+}
+
+impl Templates {
+    pub fn is_set(&self) -> bool {
+        self.each_match.is_some() || self.line.is_some() || self.file.is_some()
+    }
+}
+
 #[derive(Clone)]
 pub struct SearchParams {
     pub regexps: Vec<String>,
@@ -108,7 +131,16 @@ pub struct SearchParams {
     pub hidden: bool,
     pub line_number: bool,
     pub files: bool,
+    pub name_regexp: bool,
+    pub dirs: bool,
+    pub compact_dirs: bool,
+    pub flat: bool,
+    pub wrap: bool,
+    pub syntax: bool,
     pub count: bool,
+    pub totals: bool,
+    pub long: bool,
+    pub permissions: bool,
     pub links: bool,
     pub trim: bool,
     pub ignore: bool,
@@ -118,8 +150,10 @@ pub struct SearchParams {
     pub after_context: usize,
     pub overview: bool,
     pub overview_only: bool,
+    pub overview_by: Option<OverviewBy>,
     pub branch_each: usize,
     pub pcre2: bool,
+    pub tally: Option<String>,
 }
 
 #[derive(Clone)]
@@ -129,13 +163,23 @@ pub struct Config {
     pub with_bold: bool,
     pub with_colors: bool,
     pub branch_len: usize,
+    pub tab_width: usize,
+    pub syntax_theme: String,
     pub chars: Characters,
     pub colors: Colors,
     pub threads: usize,
     pub selection_file: Option<PathBuf>,
+    pub quickfix_file: Option<PathBuf>,
     pub repeat_file: Option<PathBuf>,
     pub select: bool,
     pub menu: bool,
+    pub output_format: Option<OutputFormat>,
+    pub rule_names: Vec<Option<String>>,
+    pub templates: Templates,
+    pub null: bool,
+    pub hyperlink: Option<Hyperlink>,
+    pub ls_colors: Option<LsColors>,
+    pub icons: Option<Icons>,
     pub live: bool,
     pub live_delay: Option<u64>,
     pub auto_open: bool,
@@ -153,7 +197,7 @@ fn canonicalize(p: &Path) -> Result<PathBuf, Message> {
         .map_err(|_| mes!("failed to canonicalize path `{}`", p.to_string_lossy()))
 }
 
-fn process_path(input: &Path, check_exists: bool) -> Result<PathBuf, Message> {
+pub fn process_path(input: &Path, check_exists: bool) -> Result<PathBuf, Message> {
     let mut components = input.components();
     let mut path = PathBuf::new();
     match components.next() {
@@ -198,6 +242,24 @@ pub fn get_matches(args: Vec<OsString>) -> Result<ArgMatches, Error> {
     generate_command().try_get_matches_from(get_all_args(args))
 }
 
+fn rule_names(args: &Args) -> Result<Vec<Option<String>>, Message> {
+    if args.rule_name.len() > args.regexp.len() {
+        return Err(mes!(
+            "got {} rule names for {} -e patterns",
+            args.rule_name.len(),
+            args.regexp.len()
+        ));
+    }
+    if args.rule_name.is_empty() {
+        return Ok(Vec::new());
+    }
+    let positional = args.positional_regexp.as_ref().map(|_| None);
+    Ok(positional
+        .into_iter()
+        .chain((0..args.regexp.len()).map(|i| args.rule_name.get(i).cloned()))
+        .collect())
+}
+
 fn apply_matches(
     base_non_search: NonSearchFields,
     matches: &ArgMatches,
@@ -210,9 +272,16 @@ fn apply_matches(
             |id: &str| matches.value_source(id) == Some(clap::parser::ValueSource::CommandLine);
         let mut c = (*base_config()).clone();
         c.selection_file = base_non_search.selection_file;
+        c.quickfix_file = base_non_search.quickfix_file;
         c.repeat_file = base_non_search.repeat_file;
         c.select = base_non_search.select;
         c.menu = base_non_search.menu;
+        c.output_format = base_non_search.output_format;
+        c.templates = base_non_search.templates;
+        c.null = base_non_search.null;
+        c.hyperlink = base_non_search.hyperlink;
+        c.ls_colors = base_non_search.ls_colors;
+        c.icons = base_non_search.icons;
         c.live = base_non_search.live;
         c.live_delay = base_non_search.live_delay;
         c.auto_open = base_non_search.auto_open;
@@ -233,6 +302,7 @@ fn apply_matches(
                 c.search.regexps.push(expr.clone());
             }
             c.search.regexps.extend(args.regexp.iter().cloned());
+            c.rule_names = rule_names(&args)?;
         }
         if applies(args::GLOB) {
             c.search.globs = args.glob.clone();
@@ -246,9 +316,36 @@ fn apply_matches(
         if applies(args::FILES) {
             c.search.files = args.files;
         }
+        if applies(args::NAME_REGEXP) {
+            c.search.name_regexp = args.name_regexp;
+        }
+        if applies(args::DIRS) {
+            c.search.dirs = args.dirs;
+        }
+        if applies(args::COMPACT_DIRS) {
+            c.search.compact_dirs = args.compact_dirs;
+        }
+        if applies(args::FLAT) {
+            c.search.flat = args.flat;
+        }
+        if applies(args::WRAP) {
+            c.search.wrap = args.wrap;
+        }
+        if applies(args::SYNTAX) {
+            c.search.syntax = args.syntax;
+        }
         if applies(args::COUNT) {
             c.search.count = args.count;
         }
+        if applies(args::TOTALS) {
+            c.search.totals = args.totals;
+        }
+        if applies(args::LONG) {
+            c.search.long = args.long;
+        }
+        if applies(args::PERMISSIONS) {
+            c.search.permissions = args.permissions;
+        }
         if applies(args::LINKS) {
             c.search.links = args.links;
         }
@@ -261,6 +358,9 @@ fn apply_matches(
         if applies(args::PCRE2) {
             c.search.pcre2 = args.pcre2;
         }
+        if applies(args::TALLY) {
+            c.search.tally = args.tally.clone();
+        }
         if applies(args::MAX_DEPTH) {
             c.search.max_depth = args.max_depth;
         }
@@ -293,9 +393,18 @@ fn apply_matches(
         if applies(args::OVERVIEW_ONLY) {
             c.search.overview_only = args.overview_only;
         }
+        if applies(args::OVERVIEW_BY) {
+            c.search.overview_by = args.overview_by;
+        }
         if applies(args::BRANCH_LEN) {
             c.branch_len = args.branch_len;
         }
+        if applies(args::TAB_WIDTH) {
+            c.tab_width = args.tab_width;
+        }
+        if applies(args::SYNTAX_THEME) {
+            c.syntax_theme = args.syntax_theme.clone();
+        }
         if applies(args::CHAR_VERTICAL) {
             c.chars.v = args.char_vertical;
         }
@@ -398,9 +507,14 @@ fn apply_matches(
         regexps.push(expr.clone());
     }
     regexps.extend(args.regexp.iter().cloned());
+    let rule_names = rule_names(&args)?;
+    if args.syntax {
+        syntax::check_theme(&args.syntax_theme)?;
+    }
     let context = args.context.unwrap_or(0);
     let branch_len = args.branch_len;
-    let chars = Config::get_characters(&args);
+    let theme = theme::load(args.theme.as_deref())?;
+    let chars = Config::get_characters(&args, matches, &theme);
     Ok(Config {
         search: SearchParams {
             path,
@@ -409,7 +523,16 @@ fn apply_matches(
             hidden: args.hidden,
             line_number: args.line_number,
             files: args.files,
+            name_regexp: args.name_regexp,
+            dirs: args.dirs,
+            compact_dirs: args.compact_dirs,
+            flat: args.flat,
+            wrap: args.wrap,
+            syntax: args.syntax,
             count: args.count,
+            totals: args.totals,
+            long: args.long,
+            permissions: args.permissions,
             links: args.links,
             trim: args.trim,
             ignore: !args.no_ignore,
@@ -419,20 +542,32 @@ fn apply_matches(
             after_context: args.after_context.unwrap_or(context),
             overview: args.overview,
             overview_only: args.overview_only,
+            overview_by: args.overview_by,
             branch_each: args.branch_each,
             pcre2: args.pcre2,
+            tally: args.tally.clone(),
         },
         is_dir,
         with_bold: !args.no_bold,
         with_colors: !args.no_color,
         branch_len,
+        tab_width: args.tab_width,
+        syntax_theme: args.syntax_theme.clone(),
         threads: base_non_search.threads,
         chars,
-        colors: Config::get_colors(&args),
+        colors: Config::get_colors(&args, theme.colors),
         selection_file: base_non_search.selection_file,
+        quickfix_file: base_non_search.quickfix_file,
         repeat_file: base_non_search.repeat_file,
         select: base_non_search.select,
         menu: base_non_search.menu,
+        output_format: base_non_search.output_format,
+        rule_names,
+        templates: base_non_search.templates,
+        null: base_non_search.null,
+        hyperlink: base_non_search.hyperlink,
+        ls_colors: base_non_search.ls_colors,
+        icons: base_non_search.icons,
         live: base_non_search.live,
         live_delay: base_non_search.live_delay,
         auto_open: base_non_search.auto_open,
@@ -448,9 +583,16 @@ fn apply_matches(
 
 struct NonSearchFields {
     selection_file: Option<PathBuf>,
+    quickfix_file: Option<PathBuf>,
     repeat_file: Option<PathBuf>,
     select: bool,
     menu: bool,
+    output_format: Option<OutputFormat>,
+    templates: Templates,
+    null: bool,
+    hyperlink: Option<Hyperlink>,
+    ls_colors: Option<LsColors>,
+    icons: Option<Icons>,
     live: bool,
     live_delay: Option<u64>,
     auto_open: bool,
@@ -500,12 +642,21 @@ impl Config {
                 c.with_bold = self.with_bold;
                 c.with_colors = self.with_colors;
                 c.branch_len = self.branch_len;
+                c.tab_width = self.tab_width;
+                c.syntax_theme = self.syntax_theme.clone();
                 c.chars = self.chars.clone();
                 c.colors = self.colors.clone();
                 c.selection_file = self.selection_file.clone();
+                c.quickfix_file = self.quickfix_file.clone();
                 c.repeat_file = self.repeat_file.clone();
                 c.select = self.select;
                 c.menu = self.menu;
+                c.output_format = self.output_format;
+                c.templates = self.templates.clone();
+                c.null = self.null;
+                c.hyperlink = self.hyperlink.clone();
+                c.ls_colors = self.ls_colors.clone();
+                c.icons = self.icons.clone();
                 c.live = self.live;
                 c.live_delay = self.live_delay;
                 c.auto_open = self.auto_open;
@@ -541,6 +692,11 @@ impl Config {
             .as_ref()
             .map(|p| process_path(p, false))
             .transpose()?;
+        let quickfix_file = args
+            .quickfix_file
+            .as_ref()
+            .map(|p| process_path(p, false))
+            .transpose()?;
         let repeat_file = args
             .repeat_file
             .as_ref()
@@ -550,6 +706,23 @@ impl Config {
         let non_search = NonSearchFields {
             select: args.select,
             menu: args.menu,
+            output_format: args
+                .output_format
+                .or(args.json.then_some(OutputFormat::Json))
+                .or(args.vimgrep.then_some(OutputFormat::Vimgrep)),
+            templates: Templates {
+                each_match: args.format.clone(),
+                line: args.format_line.clone(),
+                file: args.format_file.clone(),
+            },
+            null: args.null,
+            hyperlink: args
+                .hyperlink_format
+                .clone()
+                .filter(|_| !args.select && !args.menu && std::io::stdout().is_terminal())
+                .map(Hyperlink::new),
+            ls_colors: args.ls_colors.then(LsColors::from_env).flatten(),
+            icons: args.icons.then(|| Icons::new(&args.icon)),
             live: args.live,
             live_delay: args.live_delay,
             auto_open: args.auto_open,
@@ -558,6 +731,7 @@ impl Config {
             editor: args.editor.clone(),
             open_like: args.open_like.clone(),
             selection_file,
+            quickfix_file,
             repeat_file,
             completion_target: args.completions,
             keys: Config::get_key_bindings(&args, &matches),
@@ -567,52 +741,53 @@ impl Config {
         apply_matches(non_search, &matches, false)
     }
 
-    fn get_colors(args: &Args) -> Colors {
+    fn get_colors(args: &Args, theme: Colors) -> Colors {
         Colors {
-            file: args
-                .file_color
-                .as_ref()
-                .map(|v| v.get())
-                .unwrap_or(style::FILE_COLOR_DEFAULT),
-            dir: args
-                .dir_color
-                .as_ref()
-                .map(|v| v.get())
-                .unwrap_or(style::DIR_COLOR_DEFAULT),
+            file: args.file_color.as_ref().map_or(theme.file, |v| v.get()),
+            dir: args.dir_color.as_ref().map_or(theme.dir, |v| v.get()),
             line_number: args
                 .line_number_color
                 .as_ref()
-                .map(|v| v.get())
-                .unwrap_or(style::LINE_NUMBER_COLOR_DEFAULT),
-            text: args.text_color.as_ref().map(|v| v.get()),
-            branch: args.branch_color.as_ref().map(|v| v.get()),
+                .map_or(theme.line_number, |v| v.get()),
+            text: args.text_color.as_ref().map(|v| v.get()).or(theme.text),
+            branch: args.branch_color.as_ref().map(|v| v.get()).or(theme.branch),
             selected_bg: args
                 .selected_bg_color
                 .as_ref()
+                .map_or(theme.selected_bg, |v| v.get()),
+            selected_indicator: args
+                .selected_indicator_color
+                .as_ref()
                 .map(|v| v.get())
-                .unwrap_or(style::SELECTED_BG_DEFAULT),
-            selected_indicator: args.selected_indicator_color.as_ref().map(|v| v.get()),
+                .or(theme.selected_indicator),
             filter_highlight: args
                 .filter_highlight_color
                 .as_ref()
-                .map(|v| v.get())
-                .unwrap_or(style::FILTER_HIGHLIGHT_DEFAULT),
+                .map_or(theme.filter_highlight, |v| v.get()),
             matches: if args.match_colors.is_empty() {
-                style::MATCHED_COLORS_DEFAULT.to_vec()
+                theme.matches
             } else {
                 args.match_colors.iter().map(|v| v.get()).collect()
             },
         }
     }
 
-    fn get_characters(args: &Args) -> Characters {
-        let v = args.char_vertical;
-        let h = args.char_horizontal;
-        let tl = args.char_top_left;
-        let tr = args.char_top_right;
-        let bl = args.char_bottom_left;
-        let br = args.char_bottom_right;
-        let tee = args.char_tee;
+    fn get_characters(args: &Args, matches: &ArgMatches, theme: &Theme) -> Characters {
+        let pick = |id: &str, arg: char, themed: char| {
+            if matches.value_source(id) == Some(clap::parser::ValueSource::CommandLine) {
+                arg
+            } else {
+                themed
+            }
+        };
+        let b = &theme.branches;
+        let v = pick(args::CHAR_VERTICAL, args.char_vertical, b.v);
+        let h = pick(args::CHAR_HORIZONTAL, args.char_horizontal, b.h);
+        let tl = pick(args::CHAR_TOP_LEFT, args.char_top_left, b.tl);
+        let tr = pick(args::CHAR_TOP_RIGHT, args.char_top_right, b.tr);
+        let bl = pick(args::CHAR_BOTTOM_LEFT, args.char_bottom_left, b.bl);
+        let br = pick(args::CHAR_BOTTOM_RIGHT, args.char_bottom_right, b.br);
+        let tee = pick(args::CHAR_TEE, args.char_tee, b.tee);
         let spacer = args.branch_len;
         let ellipsis = args.ellipsis.clone();
         let selected_indicator = args.selected_indicator.clone();
@@ -664,6 +839,8 @@ impl Config {
             args::KEY_FILTER,
             args::KEY_SEARCH,
             args::KEY_SUBMIT_SEARCH,
+            args::KEY_WRAP,
+            args::KEY_SYNTAX,
         ];
         let is_user_set = |id: &str| -> bool {
             matches.value_source(id) == Some(clap::parser::ValueSource::CommandLine)
@@ -705,6 +882,8 @@ impl Config {
             filter: binding(args::KEY_FILTER, &args.key_filter),
             search: binding(args::KEY_SEARCH, &args.key_search),
             submit_search: binding(args::KEY_SUBMIT_SEARCH, &args.key_submit_search),
+            wrap: binding(args::KEY_WRAP, &args.key_wrap),
+            syntax: binding(args::KEY_SYNTAX, &args.key_syntax),
         }
     }
 }
@@ -731,6 +910,8 @@ fn key_field_by_id<'a>(args: &'a Args, id: &str) -> &'a [KeyCode] {
         _ if id == args::KEY_FILTER => &args.key_filter,
         _ if id == args::KEY_SEARCH => &args.key_search,
         _ if id == args::KEY_SUBMIT_SEARCH => &args.key_submit_search,
+        _ if id == args::KEY_WRAP => &args.key_wrap,
+        _ if id == args::KEY_SYNTAX => &args.key_syntax,
         _ => &[],
     }
 }
@@ -738,6 +919,19 @@ fn key_field_by_id<'a>(args: &'a Args, id: &str) -> &'a [KeyCode] {
 const MENU_UNSUPPORTED: &[&str] = &[
     args::SELECT,
     args::MENU,
+    args::JSON,
+    args::OUTPUT_FORMAT,
+    args::RULE_NAME,
+    args::VIMGREP,
+    args::FORMAT,
+    args::FORMAT_LINE,
+    args::FORMAT_FILE,
+    args::NULL,
+    args::HYPERLINK_FORMAT,
+    args::LS_COLORS,
+    args::ICONS,
+    args::ICON,
+    args::QUICKFIX_FILE,
     args::REPEAT,
     args::REPEAT_FILE,
     args::SELECTION_FILE,
@@ -763,6 +957,9 @@ const MENU_UNSUPPORTED: &[&str] = &[
     args::KEY_FILTER,
     args::KEY_SEARCH,
     args::KEY_SUBMIT_SEARCH,
+    args::KEY_WRAP,
+    args::KEY_SYNTAX,
+    args::THEME,
 ];
 
 fn id_to_flag(id: &str) -> String {
@@ -770,6 +967,10 @@ fn id_to_flag(id: &str) -> String {
 }
 
 impl SearchParams {
+    pub fn lists_paths(&self) -> bool {
+        (self.files || self.dirs) && self.regexps.is_empty()
+    }
+
     fn to_args(&self) -> Vec<OsString> {
         let mut args: Vec<OsString> = Vec::new();
         for r in &self.regexps {
@@ -788,9 +989,36 @@ impl SearchParams {
         if self.files {
             args.push(id_to_flag(args::FILES).into());
         }
+        if self.name_regexp {
+            args.push(id_to_flag(args::NAME_REGEXP).into());
+        }
+        if self.dirs {
+            args.push(id_to_flag(args::DIRS).into());
+        }
+        if self.compact_dirs {
+            args.push(id_to_flag(args::COMPACT_DIRS).into());
+        }
+        if self.flat {
+            args.push(id_to_flag(args::FLAT).into());
+        }
+        if self.wrap {
+            args.push(id_to_flag(args::WRAP).into());
+        }
+        if self.syntax {
+            args.push(id_to_flag(args::SYNTAX).into());
+        }
         if self.count {
             args.push(id_to_flag(args::COUNT).into());
         }
+        if self.totals {
+            args.push(id_to_flag(args::TOTALS).into());
+        }
+        if self.long {
+            args.push(id_to_flag(args::LONG).into());
+        }
+        if self.permissions {
+            args.push(id_to_flag(args::PERMISSIONS).into());
+        }
         if self.links {
             args.push(id_to_flag(args::LINKS).into());
         }
@@ -803,6 +1031,9 @@ impl SearchParams {
         if self.pcre2 {
             args.push(id_to_flag(args::PCRE2).into());
         }
+        if let Some(t) = &self.tally {
+            args.push(format!("{}={t}", id_to_flag(args::TALLY)).into());
+        }
         if let Some(d) = self.max_depth {
             args.push(format!("{}={d}", id_to_flag(args::MAX_DEPTH)).into());
         }
@@ -828,6 +1059,11 @@ impl SearchParams {
         if self.overview_only {
             args.push(id_to_flag(args::OVERVIEW_ONLY).into());
         }
+        if let Some(by) = self.overview_by
+            && let Some(v) = by.to_possible_value()
+        {
+            args.push(format!("{}={}", id_to_flag(args::OVERVIEW_BY), v.get_name()).into());
+        }
         if self.branch_each > 1 {
             args.push(
                 format!(
@@ -896,9 +1132,16 @@ pub fn parse_menu_query(query: &str) -> Result<Config, Message> {
         |id: &str| matches.value_source(id) == Some(clap::parser::ValueSource::CommandLine);
     let non_search = NonSearchFields {
         selection_file: base.selection_file.clone(),
+        quickfix_file: base.quickfix_file.clone(),
         repeat_file: base.repeat_file.clone(),
         select: false,
         menu: true,
+        output_format: None,
+        templates: Templates::default(),
+        null: false,
+        hyperlink: None,
+        ls_colors: base.ls_colors.clone(),
+        icons: base.icons.clone(),
         live: base.live,
         live_delay: base.live_delay,
         auto_open: base.auto_open,
@@ -913,12 +1156,15 @@ pub fn parse_menu_query(query: &str) -> Result<Config, Message> {
     };
     let mut c = apply_matches(non_search, &matches, true)?;
     let explicit_pattern = explicit(args::EXPRESSION_POSITIONAL) || explicit(args::EXPRESSION);
-    if explicit(args::FILES) && !explicit_pattern {
+    if (explicit(args::FILES) || explicit(args::DIRS)) && !explicit_pattern {
         c.search.regexps.clear();
     }
     if explicit_pattern && !explicit(args::FILES) {
         c.search.files = false;
     }
+    if explicit_pattern && !explicit(args::DIRS) {
+        c.search.dirs = false;
+    }
     drop(args);
     Ok(c)
 }
@@ -1057,4 +1303,25 @@ mod tests {
         assert_eq!(config.search.max_depth, Some(3));
         assert!(config.select);
     }
+
+    #[test]
+    fn test_sarif_rule_names() {
+        let config = get_config_from([
+            "x=1",
+            "--output-format=sarif",
+            "-e=dbg!",
+            "--rule-name=no_dbg",
+            "-e=a=b",
+            "-e=plain",
+        ]);
+        assert_eq!(config.search.regexps, vec!["x=1", "dbg!", "a=b", "plain"]);
+        assert_eq!(
+            config.rule_names,
+            vec![None, Some("no_dbg".to_string()), None, None]
+        );
+
+        let config = get_config_from(["--output-format=sarif", "-e=no_dbg=dbg!"]);
+        assert_eq!(config.search.regexps, vec!["no_dbg=dbg!"]);
+        assert!(config.rule_names.is_empty());
+    }
 }
@@ -0,0 +1,431 @@
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    args::names::TREEGREP_BIN,
+    config::Config,
+    match_system::{Directory, File, Line, Match, Matches},
+    paths::uri,
+    style::css_color,
+};
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn highlighted(raw: &[u8], matches: &[Match], cut: usize, c: &Config) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for m in matches {
+        let start = m.start.saturating_sub(cut).min(raw.len());
+        let end = m.end.saturating_sub(cut).min(raw.len());
+        if start >= end || start < last {
+            continue;
+        }
+        out.push_str(&escape_html(&String::from_utf8_lossy(&raw[last..start])));
+        let _ = write!(
+            out,
+            r#"<span class="match m{}">{}</span>"#,
+            m.regexp_id % c.colors.matches.len(),
+            escape_html(&String::from_utf8_lossy(&raw[start..end]))
+        );
+        last = end;
+    }
+    out.push_str(&escape_html(&String::from_utf8_lossy(&raw[last..])));
+    out
+}
+
+fn href(path: &Path, cwd: Option<&Path>) -> String {
+    match cwd.and_then(|cwd| path.strip_prefix(cwd).ok()) {
+        Some(rel) if !rel.as_os_str().is_empty() => uri(rel),
+        _ => format!("file://{}", uri(path)),
+    }
+}
+
+fn css(c: &Config) -> String {
+    let mut rules = vec![
+        "body { font-family: monospace; }".to_string(),
+        ".tree { white-space: pre; }".to_string(),
+        "summary { display: block; cursor: pointer; }".to_string(),
+        "summary::-webkit-details-marker { display: none; }".to_string(),
+        "a { color: inherit; text-decoration: none; }".to_string(),
+        "a:hover { text-decoration: underline; }".to_string(),
+    ];
+    if c.with_bold {
+        rules.push(".dir, .file, .match { font-weight: bold; }".to_string());
+    }
+    if c.with_colors {
+        rules.push(".context { opacity: 0.6; }".to_string());
+        rules.push(format!(".dir {{ color: {}; }}", css_color(c.colors.dir)));
+        rules.push(format!(".file {{ color: {}; }}", css_color(c.colors.file)));
+        rules.push(format!(
+            ".line-number {{ color: {}; }}",
+            css_color(c.colors.line_number)
+        ));
+        if let Some(color) = c.colors.branch {
+            rules.push(format!(".branch {{ color: {}; }}", css_color(color)));
+        }
+        if let Some(color) = c.colors.text {
+            rules.push(format!(".text {{ color: {}; }}", css_color(color)));
+        }
+        for (i, color) in c.colors.matches.iter().enumerate() {
+            rules.push(format!(".m{} {{ color: {}; }}", i, css_color(*color)));
+        }
+    }
+    rules.iter().map(|r| format!("{}\n", r)).collect::<String>()
+}
+
+struct HtmlWriter<'a> {
+    out: String,
+    cwd: Option<PathBuf>,
+    config: &'a Config,
+}
+
+impl HtmlWriter<'_> {
+    fn branch(&mut self, prefix: &str) {
+        if !prefix.is_empty() {
+            let _ = write!(
+                self.out,
+                r#"<span class="branch">{}</span>"#,
+                escape_html(prefix)
+            );
+        }
+    }
+
+    fn name(&mut self, class: &str, path: &Path, matches: &[Match], count: usize) {
+        let name = path
+            .file_name()
+            .map_or_else(|| path.to_string_lossy(), |n| n.to_string_lossy());
+        let _ = write!(
+            self.out,
+            r#"<span class="{}">{}</span>"#,
+            class,
+            highlighted(name.as_bytes(), matches, 0, self.config)
+        );
+        if self.config.search.count && count > 0 {
+            let _ = write!(self.out, ": {}", count);
+        }
+    }
+
+    fn dir(&mut self, dirs: &[Directory], id: usize, cur_prefix: &str, child_prefix: &str) {
+        let chars = &self.config.chars;
+        let dir = &dirs[id];
+        let files_shown = !dir.files.is_empty() && !self.config.search.dirs;
+        self.out.push_str("<details open><summary>");
+        self.branch(cur_prefix);
+        self.name(
+            "dir",
+            &dir.path,
+            &dir.name_matches,
+            dir.children.len() + dir.files.len(),
+        );
+        self.out.push_str("</summary>\n");
+        for (i, child) in dir.children.iter().enumerate() {
+            let (cur, next) = if i + 1 != dir.children.len() || files_shown {
+                (&chars.match_with_next, &chars.spacer_vert)
+            } else {
+                (&chars.match_no_next, &chars.spacer)
+            };
+            self.dir(
+                dirs,
+                *child,
+                &format!("{}{}", child_prefix, cur),
+                &format!("{}{}", child_prefix, next),
+            );
+        }
+        if files_shown {
+            for (i, file) in dir.files.iter().enumerate() {
+                let (cur, next) = if i + 1 != dir.files.len() {
+                    (&chars.match_with_next, &chars.spacer_vert)
+                } else {
+                    (&chars.match_no_next, &chars.spacer)
+                };
+                self.file(
+                    file,
+                    &format!("{}{}", child_prefix, cur),
+                    &format!("{}{}", child_prefix, next),
+                );
+            }
+        }
+        self.out.push_str("</details>\n");
+    }
+
+    fn file(&mut self, file: &File, cur_prefix: &str, line_prefix: &str) {
+        let lines_shown = !self.config.search.files && !file.lines.is_empty();
+        let count = file.lines.iter().map(|l| l.matches.len()).sum();
+        if !lines_shown {
+            self.out.push_str("<div>");
+            self.branch(cur_prefix);
+            self.name("file", &file.path, &file.name_matches, count);
+            self.out.push_str("</div>\n");
+            return;
+        }
+        self.out.push_str("<details open><summary>");
+        self.branch(cur_prefix);
+        self.name("file", &file.path, &file.name_matches, count);
+        self.out.push_str("</summary>\n");
+        let link = href(&file.path, self.cwd.as_deref());
+        for (i, line) in file.lines.iter().enumerate() {
+            let branch = if i + 1 != file.lines.len() {
+                &self.config.chars.match_with_next
+            } else {
+                &self.config.chars.match_no_next
+            };
+            self.line(line, &format!("{}{}", line_prefix, branch), &link);
+        }
+        self.out.push_str("</details>\n");
+    }
+
+    fn line(&mut self, line: &Line, prefix: &str, link: &str) {
+        let class = if line.context_offset.is_some() {
+            "text context"
+        } else {
+            "text"
+        };
+        self.out.push_str("<div>");
+        self.branch(prefix);
+        let _ = write!(
+            self.out,
+            r#"<a href="{}#L{}">"#,
+            escape_html(link),
+            line.line_num
+        );
+        if self.config.search.line_number {
+            let num = line
+                .context_offset
+                .map_or_else(|| line.line_num.to_string(), |o| format!("{:+}", o));
+            let _ = write!(self.out, r#"<span class="line-number">{}: </span>"#, num);
+        }
+        let cut = if self.config.search.trim {
+            line.content
+                .iter()
+                .take_while(|b| b.is_ascii_whitespace())
+                .count()
+        } else {
+            0
+        };
+        let _ = write!(
+            self.out,
+            r#"<span class="{}">{}</span></a></div>"#,
+            class,
+            highlighted(&line.content[cut..], &line.matches, cut, self.config)
+        );
+        self.out.push('\n');
+    }
+}
+
+pub fn write_html(out: &mut impl Write, matches: Option<&Matches>, c: &Config) -> io::Result<()> {
+    let mut w = HtmlWriter {
+        out: String::new(),
+        cwd: std::env::current_dir().ok(),
+        config: c,
+    };
+    match matches {
+        Some(Matches::Dir(dirs)) => w.dir(dirs, 0, "", ""),
+        Some(Matches::File(file)) => w.file(file, "", ""),
+        None => {}
+    }
+    write!(
+        out,
+        concat!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n",
+            "<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n",
+            "<div class=\"tree\">\n{}</div>\n</body>\n</html>\n"
+        ),
+        escape_html(&format!("{} {}", TREEGREP_BIN, c.search.regexps.join(" "))),
+        css(c),
+        w.out
+    )
+}
+
+fn language(path: &Path) -> String {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "rs" => "rust",
+        "py" | "pyi" => "python",
+        "js" | "mjs" | "cjs" => "javascript",
+        "ts" => "typescript",
+        "jsx" => "jsx",
+        "tsx" => "tsx",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => "cpp",
+        "cs" => "csharp",
+        "rb" => "ruby",
+        "kt" | "kts" => "kotlin",
+        "sh" | "bash" | "zsh" => "bash",
+        "ps1" => "powershell",
+        "md" | "markdown" => "markdown",
+        "yml" | "yaml" => "yaml",
+        "hs" => "haskell",
+        "ex" | "exs" => "elixir",
+        "erl" | "hrl" => "erlang",
+        "ml" | "mli" => "ocaml",
+        "pl" | "pm" => "perl",
+        "htm" | "html" => "html",
+        "txt" => "text",
+        other => other,
+    }
+    .to_string()
+}
+
+fn code_span(text: &str) -> String {
+    let ticks = "`".repeat(longest_run(text, '`') + 1);
+    if text.starts_with('`') || text.ends_with('`') {
+        format!("{} {} {}", ticks, text, ticks)
+    } else {
+        format!("{}{}{}", ticks, text, ticks)
+    }
+}
+
+fn longest_run(text: &str, c: char) -> usize {
+    let (mut longest, mut cur) = (0, 0);
+    for ch in text.chars() {
+        cur = if ch == c { cur + 1 } else { 0 };
+        longest = longest.max(cur);
+    }
+    longest
+}
+
+struct MarkdownWriter<'a> {
+    out: String,
+    config: &'a Config,
+}
+
+impl MarkdownWriter<'_> {
+    fn item(&mut self, depth: usize, path: &Path, dir: bool, count: usize) {
+        let mut name = path
+            .file_name()
+            .map_or_else(|| path.to_string_lossy(), |n| n.to_string_lossy())
+            .into_owned();
+        if dir {
+            name.push('/');
+        }
+        let _ = write!(self.out, "{}- {}", "  ".repeat(depth), code_span(&name));
+        if self.config.search.count && count > 0 {
+            let _ = write!(self.out, ": {}", count);
+        }
+        self.out.push('\n');
+    }
+
+    fn dir(&mut self, dirs: &[Directory], id: usize, depth: usize) {
+        let dir = &dirs[id];
+        self.item(depth, &dir.path, true, dir.children.len() + dir.files.len());
+        for child in &dir.children {
+            self.dir(dirs, *child, depth + 1);
+        }
+        if !self.config.search.dirs {
+            for file in &dir.files {
+                self.file(file, depth + 1);
+            }
+        }
+    }
+
+    fn file(&mut self, file: &File, depth: usize) {
+        let count = file.lines.iter().map(|l| l.matches.len()).sum();
+        self.item(depth, &file.path, false, count);
+        if self.config.search.files || file.lines.is_empty() {
+            return;
+        }
+        let indent = "  ".repeat(depth + 1);
+        let width = file
+            .lines
+            .iter()
+            .map(|l| l.line_num.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let lines: Vec<String> = file
+            .lines
+            .iter()
+            .map(|line| {
+                let content = String::from_utf8_lossy(&line.content);
+                let content = if self.config.search.trim {
+                    content.trim_start()
+                } else {
+                    &content
+                };
+                let sep = if line.context_offset.is_some() {
+                    '-'
+                } else {
+                    ':'
+                };
+                format!("{:>width$}{} {}", line.line_num, sep, content)
+            })
+            .collect();
+        let fence = "`".repeat(
+            lines
+                .iter()
+                .map(|l| longest_run(l, '`') + 1)
+                .max()
+                .unwrap_or(0)
+                .max(3),
+        );
+        let _ = writeln!(self.out, "{}{}{}", indent, fence, language(&file.path));
+        for line in lines {
+            let _ = writeln!(self.out, "{}{}", indent, line);
+        }
+        let _ = writeln!(self.out, "{}{}", indent, fence);
+    }
+}
+
+pub fn write_markdown(
+    out: &mut impl Write,
+    matches: Option<&Matches>,
+    c: &Config,
+) -> io::Result<()> {
+    let mut w = MarkdownWriter {
+        out: String::new(),
+        config: c,
+    };
+    match matches {
+        Some(Matches::Dir(dirs)) => w.dir(dirs, 0, 0),
+        Some(Matches::File(file)) => w.file(file, 0),
+        None => {}
+    }
+    out.write_all(w.out.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<a href="x">&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_code_span() {
+        assert_eq!(code_span("name"), "`name`");
+        assert_eq!(code_span("a`b"), "``a`b``");
+        assert_eq!(code_span("`a"), "`` `a ``");
+    }
+
+    #[test]
+    fn test_language() {
+        assert_eq!(language(Path::new("main.rs")), "rust");
+        assert_eq!(language(Path::new("x.HPP")), "cpp");
+        assert_eq!(language(Path::new("build.gradle")), "gradle");
+        assert_eq!(language(Path::new("Makefile")), "");
+    }
+}
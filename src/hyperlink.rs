@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: MIT
+
+use crate::paths::uri;
+use std::{fmt, path::Path};
+
+fn hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .map(|h| h.trim().to_string())
+        .unwrap_or_default()
+}
+
+#[derive(Clone)]
+pub struct Hyperlink {
+    format: String,
+    host: String,
+}
+
+impl Hyperlink {
+    pub fn new(format: String) -> Self {
+        let host = if format.contains("{host}") {
+            hostname()
+        } else {
+            String::new()
+        };
+        Hyperlink { format, host }
+    }
+
+    pub fn url(&self, path: &Path, line: Option<usize>) -> String {
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        self.format
+            .replace("{host}", &self.host)
+            .replace("{path}", &uri(&path))
+            .replace("{line}", &line.unwrap_or(1).to_string())
+    }
+
+    pub fn start(&self, f: &mut fmt::Formatter, path: &Path, line: Option<usize>) -> fmt::Result {
+        write!(f, "\x1b]8;;{}\x1b\\", self.url(path, line))
+    }
+
+    pub fn end(f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("\x1b]8;;\x1b\\")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::HYPERLINK_PRESETS;
+
+    #[test]
+    fn test_url() {
+        let link = Hyperlink {
+            format: "file://{host}{path}#{line}".to_string(),
+            host: "box".to_string(),
+        };
+        assert_eq!(
+            link.url(Path::new("/a b/c.rs"), Some(3)),
+            "file://box/a%20b/c.rs#3"
+        );
+        assert_eq!(link.url(Path::new("/a"), None), "file://box/a#1");
+        let cwd = uri(&std::env::current_dir().unwrap());
+        assert_eq!(
+            link.url(Path::new("c.rs"), Some(2)),
+            format!("file://box{}/c.rs#2", cwd)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_presets() {
+        let expected = [
+            ("default", "file://box/src/x.rs"),
+            ("file", "file://box/src/x.rs"),
+            ("vscode", "vscode://file/src/x.rs:7"),
+            ("vscode-insiders", "vscode-insiders://file/src/x.rs:7"),
+            ("vscodium", "vscodium://file/src/x.rs:7"),
+            ("cursor", "cursor://file/src/x.rs:7"),
+            ("macvim", "mvim://open?url=file:///src/x.rs&line=7"),
+            ("textmate", "txmt://open?url=file:///src/x.rs&line=7"),
+            ("kitty", "file://box/src/x.rs#7"),
+        ];
+        assert_eq!(HYPERLINK_PRESETS.len(), expected.len());
+        for ((name, format), (expected_name, url)) in HYPERLINK_PRESETS.iter().zip(expected) {
+            let link = Hyperlink {
+                format: format.to_string(),
+                host: "box".to_string(),
+            };
+            assert_eq!(*name, expected_name);
+            assert_eq!(link.url(Path::new("/src/x.rs"), Some(7)), url);
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_drive() {
+        let link = Hyperlink::new("vscode://file{path}:{line}".to_string());
+        assert_eq!(
+            link.url(Path::new(r"C:\src\x.rs"), Some(7)),
+            "vscode://file/C:/src/x.rs:7"
+        );
+    }
+}
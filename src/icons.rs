@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashMap;
+
+const DIR_KEY: &str = "dir";
+const FILE_KEY: &str = "file";
+
+const DIR: &str = "\u{f07b}";
+const FILE: &str = "\u{f15b}";
+
+const NAMES: &[(&str, &str)] = &[
+    ("Cargo.toml", "\u{e7a8}"),
+    ("Cargo.lock", "\u{e7a8}"),
+    ("Makefile", "\u{e779}"),
+    ("Dockerfile", "\u{f308}"),
+    (".gitignore", "\u{f1d3}"),
+    (".gitmodules", "\u{f1d3}"),
+    ("LICENSE", "\u{f02d}"),
+    ("README.md", "\u{f48a}"),
+];
+
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("rs", "\u{e7a8}"),
+    ("py", "\u{e73c}"),
+    ("js", "\u{e74e}"),
+    ("mjs", "\u{e74e}"),
+    ("ts", "\u{e628}"),
+    ("tsx", "\u{e7ba}"),
+    ("jsx", "\u{e7ba}"),
+    ("go", "\u{e627}"),
+    ("c", "\u{e61e}"),
+    ("h", "\u{e61e}"),
+    ("cpp", "\u{e61d}"),
+    ("hpp", "\u{e61d}"),
+    ("java", "\u{e738}"),
+    ("rb", "\u{e739}"),
+    ("lua", "\u{e620}"),
+    ("vim", "\u{e62b}"),
+    ("nix", "\u{f313}"),
+    ("sh", "\u{f489}"),
+    ("bash", "\u{f489}"),
+    ("zsh", "\u{f489}"),
+    ("fish", "\u{f489}"),
+    ("md", "\u{e73e}"),
+    ("json", "\u{e60b}"),
+    ("toml", "\u{e6b2}"),
+    ("yaml", "\u{e6a8}"),
+    ("yml", "\u{e6a8}"),
+    ("html", "\u{e736}"),
+    ("css", "\u{e749}"),
+    ("txt", "\u{f15c}"),
+    ("lock", "\u{f023}"),
+    ("png", "\u{f1c5}"),
+    ("jpg", "\u{f1c5}"),
+    ("jpeg", "\u{f1c5}"),
+    ("gif", "\u{f1c5}"),
+    ("svg", "\u{f1c5}"),
+    ("zip", "\u{f410}"),
+    ("tar", "\u{f410}"),
+    ("gz", "\u{f410}"),
+];
+
+pub const DIR_PREFIX: &str = "dir:";
+pub const NAME_PREFIX: &str = "name:";
+pub const EXT_PREFIX: &str = "ext:";
+
+pub fn valid_key(key: &str) -> bool {
+    key == DIR_KEY
+        || key == FILE_KEY
+        || [DIR_PREFIX, NAME_PREFIX, EXT_PREFIX]
+            .iter()
+            .any(|p| key.strip_prefix(p).is_some_and(|k| !k.is_empty()))
+}
+
+#[derive(Clone, Default)]
+pub struct Icons {
+    dirs: HashMap<String, String>,
+    names: HashMap<String, String>,
+    extensions: HashMap<String, String>,
+    dir: Option<String>,
+    file: Option<String>,
+}
+
+fn lookup<'a>(
+    overrides: &'a HashMap<String, String>,
+    key: &str,
+    defaults: &[(&str, &'static str)],
+) -> Option<&'a str> {
+    overrides.get(key).map(String::as_str).or_else(|| {
+        defaults
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, glyph)| *glyph)
+    })
+}
+
+impl Icons {
+    pub fn new(overrides: &[(String, String)]) -> Self {
+        let mut icons = Icons::default();
+        for (key, glyph) in overrides {
+            let glyph = glyph.clone();
+            if key == DIR_KEY {
+                icons.dir = Some(glyph);
+            } else if key == FILE_KEY {
+                icons.file = Some(glyph);
+            } else if let Some(k) = key.strip_prefix(DIR_PREFIX) {
+                icons.dirs.insert(k.to_string(), glyph);
+            } else if let Some(k) = key.strip_prefix(NAME_PREFIX) {
+                icons.names.insert(k.to_string(), glyph);
+            } else if let Some(k) = key.strip_prefix(EXT_PREFIX) {
+                icons.extensions.insert(k.to_ascii_lowercase(), glyph);
+            }
+        }
+        icons
+    }
+
+    pub fn icon(&self, name: &str, dir: bool) -> &str {
+        if dir {
+            return lookup(&self.dirs, name, &[])
+                .unwrap_or_else(|| self.dir.as_deref().unwrap_or(DIR));
+        }
+        let ext = name
+            .rsplit_once('.')
+            .map(|(_, e)| e.to_ascii_lowercase())
+            .unwrap_or_default();
+        lookup(&self.names, name, NAMES)
+            .or_else(|| lookup(&self.extensions, &ext, EXTENSIONS))
+            .unwrap_or_else(|| self.file.as_deref().unwrap_or(FILE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon() {
+        let icons = Icons::new(&[
+            ("ext:RS".to_string(), "R".to_string()),
+            (DIR_KEY.to_string(), "D".to_string()),
+            ("dir:src".to_string(), "S".to_string()),
+            ("name:notes".to_string(), "N".to_string()),
+        ]);
+        assert_eq!(icons.icon("main.rs", false), "R");
+        assert_eq!(icons.icon("MAIN.PY", false), "\u{e73c}");
+        assert_eq!(icons.icon("Cargo.toml", false), "\u{e7a8}");
+        assert_eq!(icons.icon("notes", false), "N");
+        assert_eq!(icons.icon("dir", false), FILE);
+        assert_eq!(icons.icon("tests", true), "D");
+        assert_eq!(icons.icon("rs", true), "D");
+        assert_eq!(icons.icon("src", true), "S");
+        assert_eq!(icons.icon("src", false), FILE);
+    }
+
+    #[test]
+    fn test_valid_key() {
+        assert!(valid_key("dir"));
+        assert!(valid_key("file"));
+        assert!(valid_key("ext:rs"));
+        assert!(valid_key("name:Cargo.toml"));
+        assert!(valid_key("dir:src"));
+        assert!(!valid_key("rs"));
+        assert!(!valid_key("ext:"));
+    }
+}
@@ -0,0 +1,389 @@
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    match_system::{Directory, File, Line, Match, Matches, Searched, collect_files},
+    paths::display_path,
+};
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn text(bytes: &[u8]) -> String {
+    escape(&String::from_utf8_lossy(bytes))
+}
+
+fn match_count(line: &Line) -> usize {
+    line.matches.iter().filter(|m| m.start < m.end).count()
+}
+
+fn line_event(out: &mut impl Write, path: &str, line: &Line) -> io::Result<()> {
+    if line.context_offset.is_some() {
+        return writeln!(
+            out,
+            r#"{{"type":"context","path":{},"line_number":{},"text":{}}}"#,
+            path,
+            line.line_num,
+            text(&line.content)
+        );
+    }
+    let submatches = line
+        .matches
+        .iter()
+        .filter(|m| m.start < m.end)
+        .map(|m| {
+            format!(
+                r#"{{"regexp_id":{},"start":{},"end":{},"text":{}}}"#,
+                m.regexp_id,
+                m.start,
+                m.end,
+                text(&line.content[m.start..m.end])
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    writeln!(
+        out,
+        r#"{{"type":"match","path":{},"line_number":{},"text":{},"submatches":[{}]}}"#,
+        path,
+        line.line_num,
+        text(&line.content),
+        submatches
+    )
+}
+
+pub fn write_events(out: &mut impl Write, matches: Option<&Matches>) -> io::Result<()> {
+    let cwd: Option<PathBuf> = std::env::current_dir().ok();
+    let files = matches.map(collect_files).unwrap_or_default();
+    let (mut total_lines, mut total_matches) = (0, 0);
+    for file in &files {
+        let path = escape(&display_path(&file.path, cwd.as_deref()));
+        writeln!(out, r#"{{"type":"begin","path":{}}}"#, path)?;
+        let (mut lines, mut count) = (0, 0);
+        for line in &file.lines {
+            line_event(out, &path, line)?;
+            if line.context_offset.is_none() {
+                lines += 1;
+                count += match_count(line);
+            }
+        }
+        writeln!(
+            out,
+            r#"{{"type":"end","path":{},"lines":{},"matches":{}}}"#,
+            path, lines, count
+        )?;
+        total_lines += lines;
+        total_matches += count;
+    }
+    writeln!(
+        out,
+        r#"{{"type":"summary","files":{},"lines":{},"matches":{}}}"#,
+        files.len(),
+        total_lines,
+        total_matches
+    )
+}
+
+fn optional_path(path: Option<&Path>) -> String {
+    path.map_or_else(|| "null".to_string(), |p| escape(&p.to_string_lossy()))
+}
+
+fn name(path: &Path) -> String {
+    escape(
+        &path
+            .file_name()
+            .map_or_else(|| path.to_string_lossy(), |n| n.to_string_lossy()),
+    )
+}
+
+fn matches_array(matches: &[Match]) -> String {
+    let items = matches
+        .iter()
+        .filter(|m| m.start < m.end)
+        .map(|m| {
+            format!(
+                r#"{{"regexp_id":{},"start":{},"end":{}}}"#,
+                m.regexp_id, m.start, m.end
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("[{}]", items)
+}
+
+fn file_object(file: &File, cwd: Option<&Path>) -> String {
+    let lines = file
+        .lines
+        .iter()
+        .map(|line| {
+            format!(
+                r#"{{"line_number":{},"text":{},"context":{},"matches":{}}}"#,
+                line.line_num,
+                text(&line.content),
+                line.context_offset.is_some(),
+                matches_array(&line.matches)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        r#"{{"type":"file","name":{},"path":{},"linked":{},"name_matches":{},"count":{},"lines":[{}]}}"#,
+        name(&file.path),
+        escape(&display_path(&file.path, cwd)),
+        optional_path(file.linked.as_deref()),
+        matches_array(&file.name_matches),
+        file.lines.iter().map(match_count).sum::<usize>(),
+        lines
+    )
+}
+
+fn dir_object(dirs: &[Directory], id: usize, cwd: Option<&Path>) -> String {
+    let dir = &dirs[id];
+    let children = dir
+        .children
+        .iter()
+        .map(|c| dir_object(dirs, *c, cwd))
+        .collect::<Vec<_>>()
+        .join(",");
+    let files = dir
+        .files
+        .iter()
+        .map(|f| file_object(f, cwd))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        concat!(
+            r#"{{"type":"directory","name":{},"path":{},"linked":{},"name_matches":{},"#,
+            r#""count":{},"totals":{{"files":{},"lines":{},"matches":{}}},"#,
+            r#""children":[{}],"files":[{}]}}"#
+        ),
+        name(&dir.path),
+        escape(&display_path(&dir.path, cwd)),
+        optional_path(dir.linked.as_deref()),
+        matches_array(&dir.name_matches),
+        dir.children.len() + dir.files.len(),
+        dir.totals.files,
+        dir.totals.lines,
+        dir.totals.matches,
+        children,
+        files
+    )
+}
+
+pub fn write_tree(out: &mut impl Write, matches: Option<&Matches>) -> io::Result<()> {
+    let cwd: Option<PathBuf> = std::env::current_dir().ok();
+    let document = match matches {
+        Some(Matches::Dir(dirs)) => dir_object(dirs, 0, cwd.as_deref()),
+        Some(Matches::File(file)) => file_object(file, cwd.as_deref()),
+        None => "null".to_string(),
+    };
+    writeln!(out, "{}", document)
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(TABLE[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn rg_data(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => format!(r#"{{"text":{}}}"#, escape(s)),
+        Err(_) => format!(r#"{{"bytes":{}}}"#, escape(&base64(bytes))),
+    }
+}
+
+fn rg_elapsed(elapsed: Duration) -> String {
+    let elapsed = if cfg!(feature = "test") {
+        Duration::ZERO
+    } else {
+        elapsed
+    };
+    format!(
+        r#"{{"secs":{},"nanos":{},"human":"{:.6}s"}}"#,
+        elapsed.as_secs(),
+        elapsed.subsec_nanos(),
+        elapsed.as_secs_f64()
+    )
+}
+
+#[derive(Default)]
+struct RgStats {
+    elapsed: Duration,
+    searches: usize,
+    searches_with_match: usize,
+    bytes_searched: u64,
+    bytes_printed: usize,
+    matched_lines: usize,
+    matches: usize,
+}
+
+impl RgStats {
+    fn add(&mut self, other: &RgStats) {
+        self.elapsed += other.elapsed;
+        self.searches += other.searches;
+        self.searches_with_match += other.searches_with_match;
+        self.bytes_searched += other.bytes_searched;
+        self.bytes_printed += other.bytes_printed;
+        self.matched_lines += other.matched_lines;
+        self.matches += other.matches;
+    }
+
+    fn to_json(&self) -> String {
+        format!(
+            concat!(
+                r#"{{"elapsed":{},"searches":{},"searches_with_match":{},"bytes_searched":{},"#,
+                r#""bytes_printed":{},"matched_lines":{},"matches":{}}}"#
+            ),
+            rg_elapsed(self.elapsed),
+            self.searches,
+            self.searches_with_match,
+            self.bytes_searched,
+            self.bytes_printed,
+            self.matched_lines,
+            self.matches
+        )
+    }
+}
+
+fn rg_line(path: &str, line: &Line) -> String {
+    let mut full = line.content.clone();
+    full.extend_from_slice(line.ending.as_bytes());
+    let submatches = line
+        .matches
+        .iter()
+        .filter(|m| m.start < m.end)
+        .map(|m| {
+            format!(
+                r#"{{"match":{},"start":{},"end":{}}}"#,
+                rg_data(&line.content[m.start..m.end]),
+                m.start,
+                m.end
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        r#"{{"type":"{}","data":{{"path":{},"lines":{},"line_number":{},"absolute_offset":{},"submatches":[{}]}}}}"#,
+        if line.context_offset.is_some() {
+            "context"
+        } else {
+            "match"
+        },
+        path,
+        rg_data(&full),
+        line.line_num,
+        line.byte_offset,
+        submatches
+    )
+}
+
+pub fn write_rg(
+    out: &mut impl Write,
+    matches: Option<&Matches>,
+    searched: Searched,
+    elapsed_total: Duration,
+) -> io::Result<()> {
+    let cwd: Option<PathBuf> = std::env::current_dir().ok();
+    let mut total = RgStats::default();
+    for file in matches.map(collect_files).unwrap_or_default() {
+        let path = rg_data(display_path(&file.path, cwd.as_deref()).as_bytes());
+        let mut stats = RgStats {
+            elapsed: file.elapsed,
+            searches: 1,
+            searches_with_match: 1,
+            bytes_searched: file.bytes_searched,
+            ..Default::default()
+        };
+        let mut printed = format!(r#"{{"type":"begin","data":{{"path":{}}}}}"#, path);
+        printed.push('\n');
+        for line in &file.lines {
+            printed.push_str(&rg_line(&path, line));
+            printed.push('\n');
+            if line.context_offset.is_none() {
+                stats.matched_lines += 1;
+                stats.matches += match_count(line);
+            }
+        }
+        stats.bytes_printed = if cfg!(feature = "test") {
+            0
+        } else {
+            printed.len()
+        };
+        out.write_all(printed.as_bytes())?;
+        writeln!(
+            out,
+            r#"{{"type":"end","data":{{"path":{},"binary_offset":null,"stats":{}}}}}"#,
+            path,
+            stats.to_json()
+        )?;
+        total.add(&stats);
+    }
+    total.searches = searched.files.max(total.searches);
+    total.bytes_searched = searched.bytes.max(total.bytes_searched);
+    writeln!(
+        out,
+        r#"{{"type":"summary","data":{{"elapsed_total":{},"stats":{}}}}}"#,
+        rg_elapsed(elapsed_total),
+        total.to_json()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("plain"), r#""plain""#);
+        assert_eq!(escape("a \"b\" \\ c"), r#""a \"b\" \\ c""#);
+        assert_eq!(escape("tab\there\n"), r#""tab\there\n""#);
+        assert_eq!(escape("\u{1}"), r#""\u0001""#);
+        assert_eq!(escape("ünï"), r#""ünï""#);
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foob"), "Zm9vYg==");
+        assert_eq!(base64(&[0xff, 0xfe, 0x00]), "//4A");
+    }
+}
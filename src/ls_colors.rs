@@ -0,0 +1,220 @@
+// SPDX-License-Identifier: MIT
+
+use crossterm::style::Color;
+use std::{collections::HashMap, fs::Metadata, path::Path};
+
+pub const LS_COLORS_ENV_NAME: &str = "LS_COLORS";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Kind {
+    File,
+    Dir,
+    Link,
+    Orphan,
+    Exec,
+    Pipe,
+    Socket,
+    Block,
+    Char,
+}
+
+impl Kind {
+    fn key(self) -> &'static str {
+        match self {
+            Kind::File => "fi",
+            Kind::Dir => "di",
+            Kind::Link => "ln",
+            Kind::Orphan => "or",
+            Kind::Exec => "ex",
+            Kind::Pipe => "pi",
+            Kind::Socket => "so",
+            Kind::Block => "bd",
+            Kind::Char => "cd",
+        }
+    }
+}
+
+#[cfg(unix)]
+fn special_kind(m: &Metadata) -> Option<Kind> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+    let t = m.file_type();
+    if t.is_fifo() {
+        Some(Kind::Pipe)
+    } else if t.is_socket() {
+        Some(Kind::Socket)
+    } else if t.is_block_device() {
+        Some(Kind::Block)
+    } else if t.is_char_device() {
+        Some(Kind::Char)
+    } else if t.is_file() && m.permissions().mode() & 0o111 != 0 {
+        Some(Kind::Exec)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+fn special_kind(_m: &Metadata) -> Option<Kind> {
+    None
+}
+
+fn kind_of(m: &Metadata) -> Kind {
+    if m.is_dir() {
+        Kind::Dir
+    } else {
+        special_kind(m).unwrap_or(Kind::File)
+    }
+}
+
+fn sgr_color(sgr: &str) -> Option<Color> {
+    let codes: Vec<u8> = sgr.split(';').filter_map(|c| c.parse().ok()).collect();
+    let mut color = None;
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            c @ 30..=37 => color = Some(Color::AnsiValue(c - 30)),
+            c @ 90..=97 => color = Some(Color::AnsiValue(c - 90 + 8)),
+            38 => match codes.get(i + 1) {
+                Some(5) => {
+                    color = codes.get(i + 2).map(|&n| Color::AnsiValue(n));
+                    i += 2;
+                }
+                Some(2) => {
+                    if let Some(&[r, g, b]) = codes.get(i + 2..i + 5) {
+                        color = Some(Color::Rgb { r, g, b });
+                    }
+                    i += 4;
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        i += 1;
+    }
+    color
+}
+
+#[derive(Clone)]
+pub struct LsColors {
+    kinds: HashMap<String, Color>,
+    suffixes: Vec<(String, Color)>,
+    link_target: bool,
+}
+
+impl LsColors {
+    pub fn from_env() -> Option<Self> {
+        std::env::var(LS_COLORS_ENV_NAME)
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(|v| Self::parse(&v))
+    }
+
+    fn parse(value: &str) -> Self {
+        let mut colors = LsColors {
+            kinds: HashMap::new(),
+            suffixes: Vec::new(),
+            link_target: false,
+        };
+        for (key, sgr) in value.split(':').filter_map(|e| e.split_once('=')) {
+            if key == Kind::Link.key() && sgr == "target" {
+                colors.link_target = true;
+                continue;
+            }
+            let Some(color) = sgr_color(sgr) else {
+                continue;
+            };
+            match key.strip_prefix('*') {
+                Some(suffix) => colors.suffixes.push((suffix.to_lowercase(), color)),
+                None => {
+                    colors.kinds.insert(key.to_string(), color);
+                }
+            }
+        }
+        colors
+    }
+
+    fn suffix(&self, name: &str) -> Option<Color> {
+        let name = name.to_lowercase();
+        self.suffixes
+            .iter()
+            .rev()
+            .find(|(s, _)| name.ends_with(s.as_str()))
+            .map(|(_, c)| *c)
+    }
+
+    fn lookup(&self, kind: Kind, name: &str) -> Option<Color> {
+        let own = self.kinds.get(kind.key()).copied();
+        match kind {
+            Kind::File => self.suffix(name).or(own),
+            Kind::Exec => own
+                .or_else(|| self.suffix(name))
+                .or_else(|| self.kinds.get(Kind::File.key()).copied()),
+            Kind::Orphan => own.or_else(|| self.kinds.get(Kind::Link.key()).copied()),
+            _ => own,
+        }
+    }
+
+    pub fn color(&self, path: &Path) -> Option<Color> {
+        let name = path.file_name()?.to_string_lossy();
+        let link = path.symlink_metadata().ok()?;
+        let kind = if link.is_symlink() {
+            match path.metadata() {
+                Ok(target) if self.link_target => kind_of(&target),
+                Ok(_) => Kind::Link,
+                Err(_) => Kind::Orphan,
+            }
+        } else {
+            kind_of(&link)
+        };
+        self.lookup(kind, &name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sgr_color() {
+        assert_eq!(sgr_color("01;34"), Some(Color::AnsiValue(4)));
+        assert_eq!(sgr_color("38;5;208"), Some(Color::AnsiValue(208)));
+        assert_eq!(
+            sgr_color("1;38;2;10;20;30"),
+            Some(Color::Rgb {
+                r: 10,
+                g: 20,
+                b: 30
+            })
+        );
+        assert_eq!(sgr_color("92"), Some(Color::AnsiValue(10)));
+        assert_eq!(sgr_color("01"), None);
+    }
+
+    #[test]
+    fn test_lookup() {
+        let colors = LsColors::parse("di=01;34:ln=01;36:ex=01;32:*.rs=33:*.tar.gz=31:fi=37");
+        assert_eq!(colors.lookup(Kind::Dir, "src"), Some(Color::AnsiValue(4)));
+        assert_eq!(
+            colors.lookup(Kind::File, "main.RS"),
+            Some(Color::AnsiValue(3))
+        );
+        assert_eq!(
+            colors.lookup(Kind::File, "a.tar.gz"),
+            Some(Color::AnsiValue(1))
+        );
+        assert_eq!(
+            colors.lookup(Kind::Exec, "build.rs"),
+            Some(Color::AnsiValue(2))
+        );
+        assert_eq!(
+            colors.lookup(Kind::File, "notes"),
+            Some(Color::AnsiValue(7))
+        );
+        assert_eq!(colors.lookup(Kind::Pipe, "fifo"), None);
+        assert_eq!(
+            colors.lookup(Kind::Orphan, "gone"),
+            Some(Color::AnsiValue(6))
+        );
+        assert!(LsColors::parse("ln=target").link_target);
+    }
+}
@@ -3,23 +3,37 @@
 mod args;
 mod config;
 mod errors;
+mod export;
+mod hyperlink;
+mod icons;
+mod json;
 mod log;
+mod ls_colors;
 mod match_system;
 mod matcher;
 mod menu;
+mod meta;
+mod output;
+mod paths;
+mod sarif;
 mod style;
+mod syntax;
 mod term;
+mod theme;
+mod width;
 mod writer;
+use args::OutputFormat;
 use clap::ArgMatches;
 use config::Config;
 use errors::Message;
-use match_system::Matches;
+use match_system::{Matches, Searched};
 use std::{
     io::{StdoutLock, stdout},
     sync::{Arc, atomic::AtomicBool},
+    time::Instant,
 };
 use term::Term;
-use writer::{matches_to_display_lines, write_results};
+use writer::{matches_to_display_lines, write_results, write_templates};
 
 fn main() {
     if cfg!(debug_assertions) {
@@ -112,11 +126,13 @@ fn run(term: &mut Term, c: Config) -> Result<(), Message> {
     config::set_config(c);
     let c = config::base_config();
 
-    let matches = if c.menu && c.search.regexps.is_empty() && !c.search.files {
-        None
-    } else {
-        matcher::search(Arc::new(AtomicBool::new(false)), Arc::clone(&c))?
-    };
+    let started = Instant::now();
+    let (matches, searched) =
+        if c.menu && c.search.regexps.is_empty() && !c.search.files && !c.search.dirs {
+            (None, Searched::default())
+        } else {
+            matcher::search(Arc::new(AtomicBool::new(false)), Arc::clone(&c))?
+        };
 
     if c.auto_open
         && let Some(m) = matches.as_ref()
@@ -126,6 +142,33 @@ fn run(term: &mut Term, c: Config) -> Result<(), Message> {
         return Ok(());
     }
 
+    if let Some(f) = &c.quickfix_file {
+        output::write_quickfix(f, matches.as_ref()).map_err(|e| mes!("{}", e))?;
+    }
+
+    if let Some(format) = c.output_format {
+        return match format {
+            OutputFormat::Json => json::write_events(term, matches.as_ref()),
+            OutputFormat::TreeJson => json::write_tree(term, matches.as_ref()),
+            OutputFormat::RgJson => {
+                json::write_rg(term, matches.as_ref(), searched, started.elapsed())
+            }
+            OutputFormat::Vimgrep => output::write_vimgrep(term, matches.as_ref()),
+            OutputFormat::Sarif => sarif::write_sarif(term, matches.as_ref(), &c),
+            OutputFormat::Html => export::write_html(term, matches.as_ref(), &c),
+            OutputFormat::Markdown => export::write_markdown(term, matches.as_ref(), &c),
+        }
+        .map_err(|e| mes!("{}", e));
+    }
+
+    if c.null {
+        return output::write_null(term, matches.as_ref()).map_err(|e| mes!("{}", e));
+    }
+
+    if c.templates.is_set() {
+        return write_templates(term, matches.as_ref(), &c).map_err(|e| mes!("{}", e));
+    }
+
     if c.select || c.menu {
         if matches.is_none() && !c.menu {
             Ok(())
@@ -137,6 +180,9 @@ fn run(term: &mut Term, c: Config) -> Result<(), Message> {
             })
         }
     } else if let Some(m) = matches {
+        if c.search.wrap {
+            term::set_plain_width();
+        }
         let lines = matches_to_display_lines(&m, Arc::clone(&c))?;
         write_results(term, &lines).map_err(|e| mes!("{}", e))
     } else {
@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: MIT
 
-use crate::errors::Message;
-use std::path::{Path, PathBuf};
+use crate::{errors::Message, meta::Meta};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 pub fn wrap_dirs(dirs: Vec<Directory>) -> Option<Matches> {
     if dirs.first().unwrap().children.is_empty() && dirs.first().unwrap().files.is_empty() {
@@ -11,10 +14,16 @@ pub fn wrap_dirs(dirs: Vec<Directory>) -> Option<Matches> {
 }
 
 pub fn wrap_file(file: Option<File>, files_no_regex: bool) -> Option<Matches> {
-    file.filter(|f| !f.lines.is_empty() || files_no_regex)
+    file.filter(|f| !f.lines.is_empty() || !f.name_matches.is_empty() || files_no_regex)
         .map(Matches::File)
 }
 
+#[derive(Clone, Copy, Default)]
+pub struct Searched {
+    pub files: usize,
+    pub bytes: u64,
+}
+
 pub enum Matches {
     Dir(Vec<Directory>),
     File(File),
@@ -25,6 +34,8 @@ pub struct Directory {
     pub linked: Option<PathBuf>,
     pub children: Vec<usize>,
     pub files: Vec<File>,
+    pub name_matches: Vec<Match>,
+    pub totals: Totals,
 }
 
 impl Directory {
@@ -34,14 +45,62 @@ impl Directory {
             linked: get_linked(path, links),
             children: Vec::new(),
             files: Vec::new(),
+            name_matches: Vec::new(),
+            totals: Totals::default(),
         })
     }
 }
 
+#[derive(Clone, Copy, Default)]
+pub struct Totals {
+    pub files: usize,
+    pub lines: usize,
+    pub matches: usize,
+}
+
+pub fn compute_totals(dirs: &mut [Directory]) {
+    for i in (0..dirs.len()).rev() {
+        let mut totals = Totals::default();
+        for file in &dirs[i].files {
+            totals.files += 1;
+            for line in file.lines.iter().filter(|l| l.context_offset.is_none()) {
+                totals.lines += 1;
+                totals.matches += line.matches.len();
+            }
+        }
+        for &child in &dirs[i].children {
+            let child = dirs[child].totals;
+            totals.files += child.files;
+            totals.lines += child.lines;
+            totals.matches += child.matches;
+        }
+        dirs[i].totals = totals;
+    }
+}
+
+pub fn collect_files(matches: &Matches) -> Vec<&File> {
+    fn walk<'a>(dirs: &'a [Directory], id: usize, files: &mut Vec<&'a File>) {
+        for child in &dirs[id].children {
+            walk(dirs, *child, files);
+        }
+        files.extend(dirs[id].files.iter());
+    }
+    let mut files = Vec::new();
+    match matches {
+        Matches::Dir(dirs) => walk(dirs, 0, &mut files),
+        Matches::File(file) => files.push(file),
+    }
+    files
+}
+
 pub struct File {
     pub path: PathBuf,
     pub lines: Vec<Line>,
     pub linked: Option<PathBuf>,
+    pub name_matches: Vec<Match>,
+    pub elapsed: Duration,
+    pub bytes_searched: u64,
+    pub meta: Option<Meta>,
 }
 
 impl File {
@@ -50,8 +109,17 @@ impl File {
             linked: get_linked(&path, links),
             path,
             lines: Vec::new(),
+            name_matches: Vec::new(),
+            elapsed: Duration::ZERO,
+            bytes_searched: 0,
+            meta: None,
         })
     }
+
+    pub fn with_meta(mut self, meta: Option<Meta>) -> Self {
+        self.meta = meta;
+        self
+    }
 }
 
 fn get_linked(path: &Path, links: bool) -> Option<PathBuf> {
@@ -85,7 +153,7 @@ impl Match {
         }
     }
 
-    fn remove_overlapping(matches: &mut [Match]) {
+    pub fn remove_overlapping(matches: &mut [Match]) {
         if matches.is_empty() {
             return;
         }
@@ -103,27 +171,44 @@ impl Match {
 
 pub struct Line {
     pub content: Vec<u8>,
+    pub ending: &'static str,
     pub matches: Vec<Match>,
     pub line_num: usize,
+    pub byte_offset: usize,
     pub context_offset: Option<isize>,
 }
 
 impl Line {
-    pub fn new(content: Vec<u8>, mut matches: Vec<Match>, line_num: usize) -> Self {
+    pub fn new(
+        content: Vec<u8>,
+        ending: &'static str,
+        mut matches: Vec<Match>,
+        line_num: usize,
+        byte_offset: usize,
+    ) -> Self {
         Match::remove_overlapping(&mut matches);
         Self {
             content,
+            ending,
             matches,
             line_num,
+            byte_offset,
             context_offset: None,
         }
     }
 
-    pub fn new_context(content: Vec<u8>, line_num: usize) -> Self {
+    pub fn new_context(
+        content: Vec<u8>,
+        ending: &'static str,
+        line_num: usize,
+        byte_offset: usize,
+    ) -> Self {
         Self {
             content,
+            ending,
             matches: Vec::new(),
             line_num,
+            byte_offset,
             context_offset: None,
         }
     }
@@ -3,15 +3,19 @@
 use crate::{
     config::Config,
     errors::Message,
-    match_system::{Directory, File, Line, Match, Matches, wrap_dirs, wrap_file},
+    match_system::{
+        Directory, File, Line, Match, Matches, Searched, compute_totals, wrap_dirs, wrap_file,
+    },
     mes,
+    meta::Meta,
 };
 use crossbeam_channel::{Receiver, Sender};
 use grep::{
     matcher::LineTerminator,
     regex::{RegexMatcher, RegexMatcherBuilder},
     searcher::{
-        BinaryDetection, MmapChoice, Searcher, SearcherBuilder, Sink, SinkContext, SinkMatch,
+        BinaryDetection, MmapChoice, Searcher, SearcherBuilder, Sink, SinkContext, SinkFinish,
+        SinkMatch,
     },
 };
 use ignore::{WalkBuilder, WalkState, overrides::OverrideBuilder};
@@ -22,24 +26,72 @@ use std::{
     path::{Path, PathBuf},
     sync::{
         Arc, Mutex,
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
     },
+    time::Instant,
 };
 
+#[derive(Default)]
+struct Counters {
+    files: AtomicUsize,
+    bytes: AtomicU64,
+}
+
+impl Counters {
+    fn add(&self, bytes: u64) {
+        self.files.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn searched(&self) -> Searched {
+        Searched {
+            files: self.files.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+enum CaptureGroup {
+    Index(usize),
+    Name(String),
+}
+
+impl CaptureGroup {
+    fn parse(group: Option<&str>) -> Option<Self> {
+        match group {
+            None | Some("0") => None,
+            Some(g) => Some(
+                g.parse::<usize>()
+                    .map_or_else(|_| Self::Name(g.to_string()), Self::Index),
+            ),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Self::Index(i) => i.to_string(),
+            Self::Name(n) => n.clone(),
+        }
+    }
+}
+
 enum Matcher {
     Regex {
         combined: RegexMatcher,
         individual: Vec<regex::bytes::Regex>,
+        group: Option<CaptureGroup>,
     },
     #[cfg(feature = "pcre2")]
     Pcre2 {
         combined: grep_pcre2::RegexMatcher,
         individual: Vec<pcre2::bytes::Regex>,
+        group: Option<CaptureGroup>,
     },
 }
 
 impl Matcher {
-    fn new(patterns: &[String], use_pcre2: bool) -> Result<Self, Message> {
+    fn new(patterns: &[String], use_pcre2: bool, group: Option<&str>) -> Result<Self, Message> {
+        let group = CaptureGroup::parse(group);
         #[cfg(feature = "pcre2")]
         if use_pcre2 {
             let combined = grep_pcre2::RegexMatcherBuilder::new()
@@ -54,9 +106,18 @@ impl Matcher {
                         .map_err(|_| mes!("pcre2 expression `{}` is invalid", p))
                 })
                 .collect::<Result<Vec<_>, _>>()?;
+            if let Some(g) = &group
+                && !individual.iter().any(|r| match g {
+                    CaptureGroup::Index(i) => *i < r.captures_len(),
+                    CaptureGroup::Name(n) => r.capture_names().iter().flatten().any(|c| c == n),
+                })
+            {
+                return Err(mes!("no expression has a capture group `{}`", g.name()));
+            }
             return Ok(Self::Pcre2 {
                 combined,
                 individual,
+                group,
             });
         }
         #[cfg(not(feature = "pcre2"))]
@@ -74,27 +135,71 @@ impl Matcher {
                 regex::bytes::Regex::new(p).map_err(|_| mes!("regex expression `{}` is invalid", p))
             })
             .collect::<Result<Vec<_>, _>>()?;
+        if let Some(g) = &group
+            && !individual.iter().any(|r| match g {
+                CaptureGroup::Index(i) => *i < r.captures_len(),
+                CaptureGroup::Name(n) => r.capture_names().flatten().any(|c| c == n),
+            })
+        {
+            return Err(mes!("no expression has a capture group `{}`", g.name()));
+        }
         Ok(Self::Regex {
             combined,
             individual,
+            group,
         })
     }
 
     fn find_all_matches(&self, bytes: &[u8], buf: &mut Vec<Match>) -> Result<(), io::Error> {
         match self {
-            Self::Regex { individual, .. } => {
+            Self::Regex {
+                individual, group, ..
+            } => {
                 for (id, regex) in individual.iter().enumerate() {
-                    for m in regex.find_iter(bytes) {
-                        buf.push(Match::new(id, m.start(), m.end()));
+                    match group {
+                        None => {
+                            for m in regex.find_iter(bytes) {
+                                buf.push(Match::new(id, m.start(), m.end()));
+                            }
+                        }
+                        Some(g) => {
+                            for caps in regex.captures_iter(bytes) {
+                                let m = match g {
+                                    CaptureGroup::Index(i) => caps.get(*i),
+                                    CaptureGroup::Name(n) => caps.name(n),
+                                };
+                                if let Some(m) = m {
+                                    buf.push(Match::new(id, m.start(), m.end()));
+                                }
+                            }
+                        }
                     }
                 }
             }
             #[cfg(feature = "pcre2")]
-            Self::Pcre2 { individual, .. } => {
+            Self::Pcre2 {
+                individual, group, ..
+            } => {
                 for (id, regex) in individual.iter().enumerate() {
-                    for m in regex.find_iter(bytes) {
-                        let m = m.map_err(io::Error::other)?;
-                        buf.push(Match::new(id, m.start(), m.end()));
+                    match group {
+                        None => {
+                            for m in regex.find_iter(bytes) {
+                                let m = m.map_err(io::Error::other)?;
+                                buf.push(Match::new(id, m.start(), m.end()));
+                            }
+                        }
+                        Some(g) => {
+                            for caps in regex.captures_iter(bytes) {
+                                let caps = caps.map_err(io::Error::other)?;
+                                let m = match g {
+                                    CaptureGroup::Index(i) => caps.get(*i),
+                                    CaptureGroup::Name(n) => caps.name(n),
+                                };
+                                if let Some(m) = m {
+                                    buf.push(Match::new(id, m.start(), m.end()));
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -111,18 +216,27 @@ impl Matcher {
     }
 }
 
+enum Found {
+    File(File),
+    Dir(PathBuf, Vec<Match>),
+}
+
 struct MatchSink<'a> {
     lines: Vec<Line>,
     matcher: &'a Matcher,
     match_buf: Vec<Match>,
     match_error: Option<String>,
+    bytes_searched: u64,
 }
 
-fn strip_line_ending(bytes: &[u8]) -> &[u8] {
-    bytes
-        .strip_suffix(b"\r\n")
-        .or_else(|| bytes.strip_suffix(b"\n"))
-        .unwrap_or(bytes)
+fn strip_line_ending(bytes: &[u8]) -> (&[u8], &'static str) {
+    if let Some(b) = bytes.strip_suffix(b"\r\n") {
+        (b, "\r\n")
+    } else if let Some(b) = bytes.strip_suffix(b"\n") {
+        (b, "\n")
+    } else {
+        (bytes, "")
+    }
 }
 
 impl<'a> Sink for MatchSink<'a> {
@@ -131,7 +245,7 @@ impl<'a> Sink for MatchSink<'a> {
     fn matched(&mut self, _searcher: &Searcher, mat: &SinkMatch<'_>) -> Result<bool, io::Error> {
         let line_bytes = mat.bytes();
         let line_num = mat.line_number().unwrap_or(0) as usize;
-        let trimmed_bytes = strip_line_ending(line_bytes);
+        let (trimmed_bytes, ending) = strip_line_ending(line_bytes);
 
         self.match_buf.clear();
         if let Err(e) = self
@@ -146,7 +260,13 @@ impl<'a> Sink for MatchSink<'a> {
             let content = trimmed_bytes.to_vec();
             let n = self.match_buf.len();
             let matches = std::mem::replace(&mut self.match_buf, Vec::with_capacity(n));
-            self.lines.push(Line::new(content, matches, line_num));
+            self.lines.push(Line::new(
+                content,
+                ending,
+                matches,
+                line_num,
+                mat.absolute_byte_offset() as usize,
+            ));
         }
 
         Ok(true)
@@ -154,16 +274,34 @@ impl<'a> Sink for MatchSink<'a> {
 
     fn context(&mut self, _searcher: &Searcher, ctx: &SinkContext<'_>) -> Result<bool, io::Error> {
         let line_bytes = ctx.bytes();
-        let trimmed_bytes = strip_line_ending(line_bytes);
+        let (trimmed_bytes, ending) = strip_line_ending(line_bytes);
         let content = trimmed_bytes.to_vec();
         let line_num = ctx.line_number().unwrap_or(0) as usize;
-        self.lines.push(Line::new_context(content, line_num));
+        self.lines.push(Line::new_context(
+            content,
+            ending,
+            line_num,
+            ctx.absolute_byte_offset() as usize,
+        ));
         Ok(true)
     }
+
+    fn finish(&mut self, _searcher: &Searcher, finish: &SinkFinish) -> Result<(), io::Error> {
+        self.bytes_searched = finish.byte_count();
+        Ok(())
+    }
 }
 
-pub fn search(abort: Arc<AtomicBool>, config: Arc<Config>) -> Result<Option<Matches>, Message> {
-    let matchers = Matcher::new(&config.search.regexps, config.search.pcre2)?;
+pub fn search(
+    abort: Arc<AtomicBool>,
+    config: Arc<Config>,
+) -> Result<(Option<Matches>, Searched), Message> {
+    let counters = Arc::new(Counters::default());
+    let matchers = Matcher::new(
+        &config.search.regexps,
+        config.search.pcre2,
+        config.search.tally.as_deref(),
+    )?;
     let mut searcher = SearcherBuilder::new()
         .line_number(true)
         .line_terminator(LineTerminator::byte(b'\n'))
@@ -173,36 +311,44 @@ pub fn search(abort: Arc<AtomicBool>, config: Arc<Config>) -> Result<Option<Matc
         .after_context(config.search.after_context)
         .build();
 
-    if config.is_dir {
+    let matches = if config.is_dir {
         let matchers = Arc::new(matchers);
-        Ok(wrap_dirs(search_dir(
+        wrap_dirs(search_dir(
             &matchers,
             &mut searcher,
+            &counters,
             abort,
             config,
-        )?))
+        )?)
     } else {
-        Ok(wrap_file(
+        wrap_file(
             search_file(
                 config.search.path.to_path_buf(),
                 &matchers,
                 &mut searcher,
+                &counters,
                 &config,
-            )?,
-            config.search.files && config.search.regexps.is_empty(),
-        ))
-    }
+            )?
+            .map(|f| {
+                let meta = config.search.long.then(|| f.path.metadata().ok()).flatten();
+                f.with_meta(meta.map(|m| Meta::new(&m)))
+            }),
+            config.search.lists_paths(),
+        )
+    };
+    Ok((matches, counters.searched()))
 }
 
 fn search_dir(
     matchers: &Arc<Matcher>,
     searcher: &mut Searcher,
+    counters: &Arc<Counters>,
     abort: Arc<AtomicBool>,
     config: Arc<Config>,
 ) -> Result<Vec<Directory>, Message> {
     let path = config.search.path.clone();
     let globs = config.search.globs.clone();
-    search_dir_impl(&path, &globs, matchers, searcher, abort, config)
+    search_dir_impl(&path, &globs, matchers, searcher, counters, abort, config)
 }
 
 fn search_dir_impl(
@@ -210,6 +356,7 @@ fn search_dir_impl(
     globs: &[String],
     matchers: &Arc<Matcher>,
     searcher: &mut Searcher,
+    counters: &Arc<Counters>,
     abort: Arc<AtomicBool>,
     config: Arc<Config>,
 ) -> Result<Vec<Directory>, Message> {
@@ -237,13 +384,14 @@ fn search_dir_impl(
         )
         .build_parallel();
 
-    let (tx, rx): (Sender<File>, Receiver<File>) = crossbeam_channel::unbounded();
+    let (tx, rx): (Sender<Found>, Receiver<Found>) = crossbeam_channel::unbounded();
     let first_err: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
 
     walker.run(|| {
         let tx = tx.clone();
         let matchers = Arc::clone(matchers);
         let mut searcher = searcher.clone();
+        let counters = Arc::clone(counters);
         let abort = Arc::clone(&abort);
         let config = Arc::clone(&config);
         let first_err = Arc::clone(&first_err);
@@ -258,15 +406,39 @@ fn search_dir_impl(
                 Err(_) => return WalkState::Continue,
             };
 
-            let is_file = entry.file_type().is_some_and(|ft| ft.is_file());
-            if !is_file {
-                return WalkState::Continue;
-            }
+            let found = match entry.file_type() {
+                Some(ft) if ft.is_file() => search_file(
+                    entry.path().to_path_buf(),
+                    &matchers,
+                    &mut searcher,
+                    &counters,
+                    &config,
+                )
+                .map(|file| {
+                    file.map(|f| {
+                        let meta = config.search.long.then(|| entry.metadata().ok());
+                        Found::File(f.with_meta(meta.flatten().map(|m| Meta::new(&m))))
+                    })
+                }),
+                Some(ft) if ft.is_dir() && config.search.name_regexp && entry.depth() > 0 => {
+                    let path = entry.into_path();
+                    match_name(&path, &matchers)
+                        .map(|m| (!m.is_empty()).then_some(Found::Dir(path, m)))
+                }
+                Some(ft)
+                    if ft.is_dir()
+                        && config.search.dirs
+                        && config.search.regexps.is_empty()
+                        && entry.depth() > 0 =>
+                {
+                    Ok(Some(Found::Dir(entry.into_path(), Vec::new())))
+                }
+                _ => return WalkState::Continue,
+            };
 
-            let path = entry.into_path();
-            match search_file(path, &matchers, &mut searcher, &config) {
-                Ok(Some(file)) => {
-                    let _ = tx.send(file);
+            match found {
+                Ok(Some(found)) => {
+                    let _ = tx.send(found);
                 }
                 Ok(None) => {}
                 Err(e) => {
@@ -294,22 +466,36 @@ fn search_file(
     pb: PathBuf,
     matcher: &Matcher,
     searcher: &mut Searcher,
+    counters: &Counters,
     config: &Config,
 ) -> Result<Option<File>, Message> {
-    if config.search.files && config.search.regexps.is_empty() {
+    if config.search.lists_paths() {
         return Ok(Some(File::from_pathbuf(pb, config.search.links)?));
     }
 
+    if config.search.name_regexp {
+        let name_matches = match_name(&pb, matcher)?;
+        if name_matches.is_empty() {
+            return Ok(None);
+        }
+        let mut file = File::from_pathbuf(pb, config.search.links)?;
+        file.name_matches = name_matches;
+        return Ok(Some(file));
+    }
+
     let mut sink = MatchSink {
         lines: Vec::with_capacity(32),
         match_buf: Vec::with_capacity(8),
         matcher,
         match_error: None,
+        bytes_searched: 0,
     };
 
+    let started = Instant::now();
     if matcher.search_into(searcher, &pb, &mut sink) {
         return Ok(None);
     }
+    counters.add(sink.bytes_searched);
     if let Some(e) = sink.match_error {
         return Err(mes!("pcre2 match error on {}: {}", pb.display(), e));
     }
@@ -324,11 +510,24 @@ fn search_file(
 
     let mut file = File::from_pathbuf(pb, config.search.links)?;
     file.lines = sink.lines;
+    file.elapsed = started.elapsed();
+    file.bytes_searched = sink.bytes_searched;
     Ok(Some(file))
 }
 
+fn match_name(path: &Path, matcher: &Matcher) -> Result<Vec<Match>, Message> {
+    let mut matches = Vec::new();
+    if let Some(name) = path.file_name() {
+        matcher
+            .find_all_matches(name.as_encoded_bytes(), &mut matches)
+            .map_err(|e| mes!("pcre2 match error on {}: {}", path.display(), e))?;
+    }
+    Match::remove_overlapping(&mut matches);
+    Ok(matches)
+}
+
 fn build_directory_tree(
-    rx: Receiver<File>,
+    rx: Receiver<Found>,
     root_path: &Path,
     links: bool,
 ) -> Result<Vec<Directory>, Message> {
@@ -338,19 +537,34 @@ fn build_directory_tree(
     path_to_index.insert(root_path.as_os_str().to_owned(), 0);
     directories.push(Directory::new(root_path, links)?);
 
-    for file in rx {
-        if let Some(dir_path) = file.path.parent() {
-            let dir_idx = get_or_create_directory(
-                &mut path_to_index,
-                &mut directories,
-                dir_path,
-                root_path,
-                links,
-            )?;
-            directories[dir_idx].files.push(file);
+    for found in rx {
+        match found {
+            Found::File(file) => {
+                if let Some(dir_path) = file.path.parent() {
+                    let dir_idx = get_or_create_directory(
+                        &mut path_to_index,
+                        &mut directories,
+                        dir_path,
+                        root_path,
+                        links,
+                    )?;
+                    directories[dir_idx].files.push(file);
+                }
+            }
+            Found::Dir(dir_path, name_matches) => {
+                let dir_idx = get_or_create_directory(
+                    &mut path_to_index,
+                    &mut directories,
+                    &dir_path,
+                    root_path,
+                    links,
+                )?;
+                directories[dir_idx].name_matches = name_matches;
+            }
         }
     }
 
+    compute_totals(&mut directories);
     Ok(directories)
 }
 
@@ -7,6 +7,7 @@ use crate::{
     match_system::Matches,
     matcher, style,
     term::Term,
+    width,
     writer::{Entry, WithFilter, matches_to_display_lines},
 };
 use crossbeam_channel::{Receiver, Sender};
@@ -30,6 +31,7 @@ use std::{
     },
     time::{Duration, Instant},
 };
+use unicode_segmentation::UnicodeSegmentation;
 
 const TOP_ROW: u16 = 0;
 const DOUBLE_CLICK_MS: Duration = Duration::from_millis(500);
@@ -130,6 +132,14 @@ fn fold_end<'e>(orig: usize, lines: &[Box<dyn Entry + 'e>]) -> usize {
     end
 }
 
+fn wrap_end<'e>(orig: usize, lines: &[Box<dyn Entry + 'e>]) -> usize {
+    let mut end = orig + 1;
+    while end < lines.len() && lines[end].is_wrap() {
+        end += 1;
+    }
+    end
+}
+
 fn str_move_back(s: &str, cursor: usize) -> usize {
     if cursor == 0 {
         return 0;
@@ -248,23 +258,30 @@ fn str_transpose_words(s: &mut String, cursor: usize) -> Option<usize> {
 }
 
 fn bar_display(prompt: &str, text: &str, cursor_byte: usize, width: usize) -> (String, u16) {
-    let prompt_chars = prompt.chars().count();
-    let cursor_chars = text[..cursor_byte].chars().count();
-    let cursor_col = prompt_chars + cursor_chars;
-    let scroll_chars = if cursor_col >= width {
+    let prompt_width = width::width(prompt);
+    let cursor_col = prompt_width + width::width(&text[..cursor_byte]);
+    let scroll = if cursor_col >= width {
         cursor_col + 1 - width
     } else {
         0
     };
-    let visible_chars = width.saturating_sub(prompt_chars);
-    let text_visible: String = text
-        .chars()
-        .skip(scroll_chars)
-        .take(visible_chars)
-        .collect();
+    let room = width.saturating_sub(prompt_width);
+    let (mut skipped, mut used) = (0, 0);
+    let mut text_visible = String::new();
+    for g in text.graphemes(true) {
+        let w = width::width(g);
+        if skipped < scroll {
+            skipped += w;
+        } else if used + w <= room {
+            used += w;
+            text_visible.push_str(g);
+        } else {
+            break;
+        }
+    }
     (
         format!("{}{}", prompt, text_visible),
-        (cursor_col - scroll_chars) as u16,
+        cursor_col.saturating_sub(skipped) as u16,
     )
 }
 
@@ -466,7 +483,7 @@ fn help_popup() -> String {
         ("alt t".into(), "transpose words"),
     ];
 
-    let nav_rows: Vec<(String, &str)> = vec![
+    let mut nav_rows: Vec<(String, &str)> = vec![
         (
             format!("{}/backtab/opt+q", format_keys(&k.search)),
             "search",
@@ -486,11 +503,17 @@ fn help_popup() -> String {
         (format_keys(&k.page_down), "page down"),
         (format_keys(&k.cycle_view), "cycle cursor position"),
         (format_keys(&k.fold), "fold/unfold"),
+        (format_keys(&k.wrap), "wrap lines"),
+    ];
+    if cfg!(feature = "syntax") {
+        nav_rows.push((format_keys(&k.syntax), "syntax highlighting"));
+    }
+    nav_rows.extend([
         ("scroll/click".into(), "scroll/click"),
         (format_keys(&k.open), "open"),
         (format_keys(&k.help), "help"),
         (format!("esc/{}", format_keys(&k.quit)), "quit"),
-    ];
+    ]);
 
     format!(
         "text bars\n\
@@ -510,13 +533,40 @@ fn help_popup() -> String {
 
 struct CurrentResults {
     lines: Vec<Box<dyn Entry>>,
+    matches: Matches,
+    config: Arc<Config>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Toggles {
+    wrap: Option<bool>,
+    syntax: Option<bool>,
+}
+
+impl Toggles {
+    fn apply(&self, config: Arc<Config>) -> Arc<Config> {
+        let wrap = self.wrap.unwrap_or(config.search.wrap);
+        let syntax = self.syntax.unwrap_or(config.search.syntax);
+        if wrap == config.search.wrap && syntax == config.search.syntax {
+            return config;
+        }
+        let mut c = (*config).clone();
+        c.search.wrap = wrap;
+        c.search.syntax = syntax;
+        Arc::new(c)
+    }
 }
 
 impl CurrentResults {
-    fn new(matches: Matches, config: Arc<Config>) -> io::Result<Self> {
-        let lines =
-            matches_to_display_lines(&matches, config).map_err(|e| io::Error::other(e.mes))?;
-        Ok(CurrentResults { lines })
+    fn new(matches: Matches, config: Arc<Config>, toggles: Toggles) -> io::Result<Self> {
+        let config = toggles.apply(config);
+        let lines = matches_to_display_lines(&matches, Arc::clone(&config))
+            .map_err(|e| io::Error::other(e.mes))?;
+        Ok(CurrentResults {
+            lines,
+            matches,
+            config,
+        })
     }
 }
 
@@ -548,6 +598,7 @@ pub struct Menu<'a, 'b> {
     window: Window,
     pending_save: bool,
     debounce_deadline: Option<Instant>,
+    toggles: Toggles,
 }
 
 impl<'a, 'b> Menu<'a, 'b> {
@@ -587,6 +638,7 @@ impl<'a, 'b> Menu<'a, 'b> {
             window: Window::new(),
             pending_save: false,
             debounce_deadline: None,
+            toggles: Toggles::default(),
         }
     }
 
@@ -638,10 +690,14 @@ impl<'a, 'b> Menu<'a, 'b> {
         } else {
             let n = lines.len();
             let mut m = vec![false; n];
-            for (i, b) in m.iter_mut().enumerate() {
-                if lines[i].filter_text().to_lowercase().contains(&lower) {
-                    *b = true;
+            let mut i = 0;
+            while i < n {
+                let end = wrap_end(i, lines);
+                let text: String = (i..end).map(|j| lines[j].filter_text()).collect();
+                if text.to_lowercase().contains(&lower) {
+                    m[i..end].fill(true);
                 }
+                i = end;
             }
             for i in (0..n).rev() {
                 if lines[i].is_path() && !m[i] {
@@ -668,12 +724,31 @@ impl<'a, 'b> Menu<'a, 'b> {
         self.visible = visible;
         self.max = self.visible.len().saturating_sub(1);
         if !self.visible.is_empty() {
-            self.selected_id = self.selected_id.min(self.max);
-            let max_cy = self.max_cursor_y();
-            if self.cursor_y > max_cy {
-                self.cursor_y = max_cy;
-            }
+            let id = self.selected_id.min(self.max);
+            self.selected_id = self.row_head(id);
+            self.cursor_y = self
+                .cursor_y
+                .min(self.max_cursor_y())
+                .saturating_sub((id - self.selected_id) as u16)
+                .max(self.start_y());
+        }
+    }
+
+    fn is_wrap_row(&self, id: usize) -> bool {
+        self.visible
+            .get(id)
+            .is_some_and(|&o| self.lines()[o].is_wrap())
+    }
+
+    fn row_head(&self, mut id: usize) -> usize {
+        while id > 0 && self.is_wrap_row(id) {
+            id -= 1;
         }
+        id
+    }
+
+    fn next_head(&self, id: usize) -> Option<usize> {
+        (id + 1..self.visible.len()).find(|&i| !self.is_wrap_row(i))
     }
 
     fn toggle_fold(&mut self) -> bool {
@@ -691,6 +766,75 @@ impl<'a, 'b> Menu<'a, 'b> {
         true
     }
 
+    fn rebuild_results(&mut self) {
+        let Some(current) = self.current.take() else {
+            return;
+        };
+        let lines = &current.lines;
+        let paths: Vec<usize> = (0..lines.len()).filter(|&i| lines[i].is_path()).collect();
+        let folded: HashSet<usize> = (0..paths.len())
+            .filter(|&n| self.folded.contains(&paths[n]))
+            .collect();
+        let selected = self.visible.get(self.selected_id).and_then(|&i| {
+            lines[i]
+                .open_info()
+                .ok()
+                .map(|o| (o.path.to_path_buf(), o.line, lines[i].is_path()))
+        });
+        let CurrentResults {
+            matches, config, ..
+        } = *current;
+        self.folded.clear();
+        if let Ok(r) = CurrentResults::new(matches, config, self.toggles) {
+            let lines = &r.lines;
+            self.folded = (0..lines.len())
+                .filter(|&i| lines[i].is_path())
+                .enumerate()
+                .filter(|(n, _)| folded.contains(n))
+                .map(|(_, i)| i)
+                .collect();
+            self.current = Some(Box::new(r));
+        }
+        self.update_visible();
+        let lines = self.lines();
+        let found = selected.and_then(|(path, line, is_path)| {
+            self.visible.iter().position(|&i| {
+                lines[i].is_path() == is_path
+                    && lines[i]
+                        .open_info()
+                        .is_ok_and(|o| o.path == path && o.line == line)
+            })
+        });
+        self.selected_id = found.unwrap_or_else(|| {
+            self.row_head(self.selected_id.min(self.visible.len().saturating_sub(1)))
+        });
+        self.cursor_y = self
+            .cursor_y
+            .min(self.start_y() + self.selected_id as u16)
+            .min(self.max_cursor_y());
+    }
+
+    fn toggle_wrap(&mut self) -> bool {
+        let Some(current) = &self.current else {
+            return false;
+        };
+        self.toggles.wrap = Some(!current.config.search.wrap);
+        self.rebuild_results();
+        true
+    }
+
+    fn toggle_syntax(&mut self) -> bool {
+        let Some(current) = &self.current else {
+            return false;
+        };
+        if !cfg!(feature = "syntax") {
+            return false;
+        }
+        self.toggles.syntax = Some(!current.config.search.syntax);
+        self.rebuild_results();
+        true
+    }
+
     fn print_line(&mut self, orig: usize) -> io::Result<()> {
         let lines = self
             .current
@@ -845,7 +989,14 @@ impl<'a, 'b> Menu<'a, 'b> {
         if self.visible.is_empty() {
             return false;
         }
-        let dist = (try_dist as usize).min(self.max - self.selected_id);
+        let target = (self.selected_id + try_dist as usize).min(self.max);
+        let target = if self.is_wrap_row(target) {
+            self.next_head(target)
+                .unwrap_or_else(|| self.row_head(target))
+        } else {
+            target
+        };
+        let dist = target.saturating_sub(self.selected_id);
         if dist == 0 {
             return false;
         }
@@ -860,7 +1011,8 @@ impl<'a, 'b> Menu<'a, 'b> {
         if self.visible.is_empty() {
             return false;
         }
-        let dist = (try_dist as usize).min(self.selected_id);
+        let target = self.row_head(self.selected_id - (try_dist as usize).min(self.selected_id));
+        let dist = self.selected_id - target;
         if dist == 0 {
             return false;
         }
@@ -888,15 +1040,21 @@ impl<'a, 'b> Menu<'a, 'b> {
     }
 
     fn bottom(&mut self) -> bool {
-        if self.visible.is_empty() || self.selected_id == self.max {
+        if self.visible.is_empty() {
+            return false;
+        }
+        let target = self.row_head(self.max);
+        if self.selected_id == target {
             return false;
         }
         self.cursor_y = if self.bot_visible() {
-            (self.cursor_y as isize + (self.max - self.selected_id) as isize) as u16
+            (self.cursor_y as isize + target as isize - self.selected_id as isize) as u16
         } else {
             self.max_cursor_y()
+                .saturating_sub((self.max - target) as u16)
+                .max(self.start_y())
         };
-        self.selected_id = self.max;
+        self.selected_id = target;
         true
     }
 
@@ -1005,12 +1163,14 @@ impl<'a, 'b> Menu<'a, 'b> {
         if row_idx < 0 || row_idx as usize >= self.visible.len() {
             return false;
         }
-        let new_id = row_idx as usize;
+        let new_id = self.row_head(row_idx as usize);
         if new_id == self.selected_id {
             return false;
         }
+        self.cursor_y = row
+            .saturating_sub((row_idx as usize - new_id) as u16)
+            .max(self.start_y());
         self.selected_id = new_id;
-        self.cursor_y = row;
         true
     }
 
@@ -1035,7 +1195,10 @@ impl<'a, 'b> Menu<'a, 'b> {
         }
         match config::parse_menu_query(&self.search) {
             Ok(new_config) => {
-                if new_config.search.regexps.is_empty() && !new_config.search.files {
+                if new_config.search.regexps.is_empty()
+                    && !new_config.search.files
+                    && !new_config.search.dirs
+                {
                     self.error_msg = None;
                     self.apply_results(None);
                     return;
@@ -1048,7 +1211,7 @@ impl<'a, 'b> Menu<'a, 'b> {
                 let tx = self.result_tx.clone();
                 std::thread::spawn(move || {
                     let result = matcher::search(abort, thread_config.clone())
-                        .map(|opt| opt.map(|m| (m, thread_config)))
+                        .map(|(opt, _)| opt.map(|m| (m, thread_config)))
                         .map_err(|e| e.mes);
                     let _ = tx.send((search_id, result));
                 });
@@ -1063,10 +1226,17 @@ impl<'a, 'b> Menu<'a, 'b> {
     fn apply_results(&mut self, result: Option<SearchResult>) {
         self.search_started = None;
         self.current = None;
-        if let Some((m, config)) = result
-            && let Ok(r) = CurrentResults::new(m, config)
-        {
-            self.current = Some(Box::new(r));
+        self.folded.clear();
+        if let Some((m, config)) = result {
+            let tally = config.search.tally.is_some();
+            if let Ok(r) = CurrentResults::new(m, config, self.toggles) {
+                if tally {
+                    self.folded = (0..r.lines.len())
+                        .filter(|&i| r.lines[i].is_path() && r.lines[i].depth() == 0)
+                        .collect();
+                }
+                self.current = Some(Box::new(r));
+            }
         }
         if self.pending_save {
             self.pending_save = false;
@@ -1076,7 +1246,6 @@ impl<'a, 'b> Menu<'a, 'b> {
         }
         self.selected_id = 0;
         self.cursor_y = self.start_y();
-        self.folded.clear();
         self.update_visible();
     }
 
@@ -1266,20 +1435,22 @@ impl<'a, 'b> Menu<'a, 'b> {
     }
 
     fn step_down(&mut self, scroll: bool) -> io::Result<()> {
-        if self.visible.is_empty() || self.selected_id >= self.max {
+        let Some(next) = self.next_head(self.selected_id) else {
             return Ok(());
-        }
-        self.destyle_selected()?;
-        self.selected_id += 1;
-        let do_scroll = if scroll {
-            self.cursor_y >= self.big_jump && !self.bot_visible()
-        } else {
-            self.cursor_y + self.big_jump >= self.max_cursor_y() && !self.bot_visible()
         };
-        if do_scroll {
-            self.scroll_fill(true)?;
-        } else {
-            self.cursor_y = (self.cursor_y + 1).min(self.max_cursor_y());
+        self.destyle_selected()?;
+        while self.selected_id < next {
+            self.selected_id += 1;
+            let do_scroll = if scroll {
+                self.cursor_y >= self.big_jump && !self.bot_visible()
+            } else {
+                self.cursor_y + self.big_jump >= self.max_cursor_y() && !self.bot_visible()
+            };
+            if do_scroll {
+                self.scroll_fill(true)?;
+            } else {
+                self.cursor_y = (self.cursor_y + 1).min(self.max_cursor_y());
+            }
         }
         self.style_selected()?;
         if self.in_menu {
@@ -1297,17 +1468,20 @@ impl<'a, 'b> Menu<'a, 'b> {
         if self.visible.is_empty() || self.selected_id == 0 {
             return Ok(());
         }
+        let prev = self.row_head(self.selected_id - 1);
         self.destyle_selected()?;
-        self.selected_id -= 1;
-        let do_scroll = if scroll {
-            self.cursor_y + self.big_jump <= self.max_cursor_y() && !self.top_visible()
-        } else {
-            self.cursor_y <= self.start_y() + self.big_jump && !self.top_visible()
-        };
-        if do_scroll {
-            self.scroll_fill(false)?;
-        } else {
-            self.cursor_y = self.cursor_y.saturating_sub(1).max(self.start_y());
+        while self.selected_id > prev {
+            self.selected_id -= 1;
+            let do_scroll = if scroll {
+                self.cursor_y + self.big_jump <= self.max_cursor_y() && !self.top_visible()
+            } else {
+                self.cursor_y <= self.start_y() + self.big_jump && !self.top_visible()
+            };
+            if do_scroll {
+                self.scroll_fill(false)?;
+            } else {
+                self.cursor_y = self.cursor_y.saturating_sub(1).max(self.start_y());
+            }
         }
         self.style_selected()?;
         if self.in_menu {
@@ -1441,6 +1615,10 @@ impl<'a, 'b> Menu<'a, 'b> {
                     (self.bottom(), false)
                 } else if keys.fold.contains(&code) {
                     (self.toggle_fold(), true)
+                } else if keys.wrap.contains(&code) {
+                    (self.toggle_wrap(), true)
+                } else if keys.syntax.contains(&code) {
+                    (self.toggle_syntax(), true)
                 } else {
                     (false, false)
                 }
@@ -1691,6 +1869,9 @@ impl<'a, 'b> Menu<'a, 'b> {
                 Event::Resize(w, h) if (self.term.height != h || self.term.width() != w) => {
                     self.term.set_dims(h, w);
                     self.set_dims(h);
+                    if self.current.as_ref().is_some_and(|c| c.config.search.wrap) {
+                        self.rebuild_results();
+                    }
                     if self.in_menu {
                         let max_cy = self.max_cursor_y();
                         if self.cursor_y > max_cy {
@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    fs::Metadata,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const UNITS: &[char] = &['K', 'M', 'G', 'T', 'P'];
+const SIZE_WIDTH: usize = 5;
+
+#[derive(Clone, Copy)]
+pub struct Meta {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub mode: Option<u32>,
+}
+
+#[cfg(unix)]
+fn mode(m: &Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(m.permissions().mode())
+}
+
+#[cfg(not(unix))]
+fn mode(_m: &Metadata) -> Option<u32> {
+    None
+}
+
+impl Meta {
+    pub fn new(m: &Metadata) -> Self {
+        Meta {
+            size: m.len(),
+            modified: m.modified().ok(),
+            mode: mode(m),
+        }
+    }
+
+    pub fn columns(&self, permissions: bool) -> String {
+        let mut out = String::new();
+        if permissions {
+            out.push_str(&self.mode.map_or_else(|| "-".repeat(10), format_mode));
+            out.push(' ');
+        }
+        out.push_str(&format!("{:>SIZE_WIDTH$} ", format_size(self.size)));
+        out.push_str(&self.modified.map_or_else(|| "-".repeat(16), format_time));
+        out
+    }
+}
+
+fn format_size(size: u64) -> String {
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if value < 10.0 {
+        format!("{:.1}{}", value, UNITS[unit])
+    } else {
+        format!("{:.0}{}", value, UNITS[unit])
+    }
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn format_time(time: SystemTime) -> String {
+    let secs = match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    };
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let rem = secs.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60
+    )
+}
+
+fn format_mode(mode: u32) -> String {
+    let kind = match mode & 0o170_000 {
+        0o040_000 => 'd',
+        0o120_000 => 'l',
+        0o010_000 => 'p',
+        0o140_000 => 's',
+        0o060_000 => 'b',
+        0o020_000 => 'c',
+        _ => '-',
+    };
+    let mut out = String::from(kind);
+    for shift in [6, 3, 0] {
+        let bits = mode >> shift;
+        out.push(if bits & 4 != 0 { 'r' } else { '-' });
+        out.push(if bits & 2 != 0 { 'w' } else { '-' });
+        out.push(if bits & 1 != 0 { 'x' } else { '-' });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0");
+        assert_eq!(format_size(1023), "1023");
+        assert_eq!(format_size(1536), "1.5K");
+        assert_eq!(format_size(20 * 1024), "20K");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0G");
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(UNIX_EPOCH), "1970-01-01 00:00");
+        assert_eq!(
+            format_time(UNIX_EPOCH + Duration::from_secs(1_709_210_096)),
+            "2024-02-29 12:34"
+        );
+        assert_eq!(
+            format_time(UNIX_EPOCH - Duration::from_secs(60)),
+            "1969-12-31 23:59"
+        );
+    }
+
+    #[test]
+    fn test_columns() {
+        let meta = Meta {
+            size: 42,
+            modified: Some(UNIX_EPOCH),
+            mode: Some(0o100_644),
+        };
+        assert_eq!(meta.columns(false), "   42 1970-01-01 00:00");
+        assert_eq!(meta.columns(true), "-rw-r--r--    42 1970-01-01 00:00");
+        assert_eq!(format_mode(0o040_755), "drwxr-xr-x");
+    }
+}
@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    match_system::{Matches, collect_files},
+    paths::display_path,
+};
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+pub fn write_vimgrep(out: &mut impl Write, matches: Option<&Matches>) -> io::Result<()> {
+    let cwd: Option<PathBuf> = std::env::current_dir().ok();
+    for file in matches.map(collect_files).unwrap_or_default() {
+        let path = display_path(&file.path, cwd.as_deref());
+        for line in &file.lines {
+            let content = String::from_utf8_lossy(&line.content);
+            for m in line.matches.iter().filter(|m| m.start < m.end) {
+                writeln!(
+                    out,
+                    "{}:{}:{}:{}",
+                    path,
+                    line.line_num,
+                    m.start + 1,
+                    content
+                )?;
+            }
+        }
+    }
+    out.flush()
+}
+
+pub fn write_null(out: &mut impl Write, matches: Option<&Matches>) -> io::Result<()> {
+    let cwd: Option<PathBuf> = std::env::current_dir().ok();
+    for file in matches.map(collect_files).unwrap_or_default() {
+        let path = cwd
+            .as_deref()
+            .and_then(|cwd| file.path.strip_prefix(cwd).ok())
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(&file.path);
+        out.write_all(path.as_os_str().as_encoded_bytes())?;
+        out.write_all(b"\0")?;
+    }
+    out.flush()
+}
+
+pub fn write_quickfix(path: &Path, matches: Option<&Matches>) -> io::Result<()> {
+    let mut out = io::BufWriter::new(std::fs::File::create(path)?);
+    write_vimgrep(&mut out, matches)
+}
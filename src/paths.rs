@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MIT
+
+use std::{
+    fmt::Write as _,
+    path::{Component, Path, Prefix},
+};
+
+pub fn display_path(path: &Path, cwd: Option<&Path>) -> String {
+    cwd.and_then(|cwd| path.strip_prefix(cwd).ok())
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            let _ = write!(out, "%{:02X}", b);
+        }
+    }
+    out
+}
+
+pub fn uri(path: &Path) -> String {
+    let mut out = String::new();
+    for c in path.components() {
+        match c {
+            Component::Prefix(p) => match p.kind() {
+                Prefix::Disk(d) | Prefix::VerbatimDisk(d) => {
+                    let _ = write!(out, "/{}:", d as char);
+                }
+                _ => out.push_str(&percent_encode(&p.as_os_str().to_string_lossy())),
+            },
+            Component::Normal(c) => {
+                if !out.is_empty() || path.has_root() {
+                    out.push('/');
+                }
+                out.push_str(&percent_encode(&c.to_string_lossy()));
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_path() {
+        let cwd = Path::new("/home/user/project");
+        assert_eq!(
+            display_path(Path::new("/home/user/project/src/main.rs"), Some(cwd)),
+            "src/main.rs"
+        );
+        assert_eq!(
+            display_path(Path::new("/elsewhere/file"), Some(cwd)),
+            "/elsewhere/file"
+        );
+        assert_eq!(display_path(cwd, Some(cwd)), "/home/user/project");
+        assert_eq!(display_path(Path::new("/a/b"), None), "/a/b");
+    }
+
+    #[test]
+    fn test_percent_encode() {
+        assert_eq!(percent_encode("plain-name_1.rs"), "plain-name_1.rs");
+        assert_eq!(percent_encode("a b#c"), "a%20b%23c");
+        assert_eq!(percent_encode("é"), "%C3%A9");
+    }
+
+    #[test]
+    fn test_uri() {
+        assert_eq!(uri(Path::new("a b/c.rs")), "a%20b/c.rs");
+        assert_eq!(uri(Path::new("/")), "");
+        #[cfg(unix)]
+        assert_eq!(uri(Path::new("/src/x.rs")), "/src/x.rs");
+        #[cfg(windows)]
+        {
+            assert_eq!(uri(Path::new(r"C:\src\x.rs")), "/C:/src/x.rs");
+            assert_eq!(uri(Path::new(r"\\?\D:\x.rs")), "/D:/x.rs");
+        }
+    }
+}
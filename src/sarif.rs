@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    config::Config,
+    json::escape,
+    match_system::{Line, Match, Matches, collect_files},
+    paths::uri,
+};
+use std::io::{self, Write};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const BASE_ID: &str = "SRCROOT";
+
+fn rule_id(c: &Config, regexp_id: usize) -> String {
+    c.rule_names
+        .get(regexp_id)
+        .cloned()
+        .flatten()
+        .unwrap_or_else(|| format!("regexp-{}", regexp_id))
+}
+
+fn rules(c: &Config) -> String {
+    c.search
+        .regexps
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            format!(
+                r#"{{"id":{},"shortDescription":{{"text":{}}}}}"#,
+                escape(&rule_id(c, i)),
+                escape(&format!("matches {}", r))
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn column(content: &[u8], offset: usize) -> usize {
+    String::from_utf8_lossy(&content[..offset]).chars().count() + 1
+}
+
+fn result(c: &Config, location: &str, line: &Line, m: &Match) -> String {
+    format!(
+        concat!(
+            r#"{{"ruleId":{},"ruleIndex":{},"level":"warning","message":{{"text":{}}},"#,
+            r#""locations":[{{"physicalLocation":{{"artifactLocation":{},"region":{{"#,
+            r#""startLine":{},"startColumn":{},"endLine":{},"endColumn":{},"snippet":{{"text":{}}}}}}}}}]}}"#
+        ),
+        escape(&rule_id(c, m.regexp_id)),
+        m.regexp_id,
+        escape(&format!(
+            "found {}",
+            String::from_utf8_lossy(&line.content[m.start..m.end])
+        )),
+        location,
+        line.line_num,
+        column(&line.content, m.start),
+        line.line_num,
+        column(&line.content, m.end),
+        escape(&String::from_utf8_lossy(&line.content))
+    )
+}
+
+pub fn write_sarif(out: &mut impl Write, matches: Option<&Matches>, c: &Config) -> io::Result<()> {
+    let root = if c.is_dir {
+        c.search.path.as_path()
+    } else {
+        c.search.path.parent().unwrap_or(&c.search.path)
+    };
+    let mut results = Vec::new();
+    for file in matches.map(collect_files).unwrap_or_default() {
+        let location = match file.path.strip_prefix(root) {
+            Ok(rel) => format!(
+                r#"{{"uri":{},"uriBaseId":"{}"}}"#,
+                escape(&uri(rel)),
+                BASE_ID
+            ),
+            Err(_) => format!(
+                r#"{{"uri":{}}}"#,
+                escape(&format!("file://{}", uri(&file.path)))
+            ),
+        };
+        for line in &file.lines {
+            for m in line.matches.iter().filter(|m| m.start < m.end) {
+                results.push(result(c, &location, line, m));
+            }
+        }
+    }
+    writeln!(
+        out,
+        concat!(
+            r#"{{"$schema":"{}","version":"2.1.0","runs":[{{"tool":{{"driver":{{"name":"{}","#,
+            r#""informationUri":"{}","rules":[{}]}}}},"#,
+            r#""originalUriBaseIds":{{"{}":{{"uri":{}}}}},"#,
+            r#""columnKind":"unicodeCodePoints","results":[{}]}}]}}"#
+        ),
+        SCHEMA,
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_HOMEPAGE"),
+        rules(c),
+        BASE_ID,
+        escape(&format!("file://{}/", uri(root))),
+        results.join(",")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column() {
+        assert_eq!(column(b"hello", 0), 1);
+        assert_eq!(column("éa".as_bytes(), 2), 2);
+    }
+}
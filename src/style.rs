@@ -12,6 +12,7 @@ pub const DIM: SetAttribute = SetAttribute(Attribute::Dim);
 
 const RED_FG: SetForegroundColor = SetForegroundColor(Color::Red);
 pub const LONG_BRANCH_FILE_SEPARATOR: &str = ", ";
+pub const TRUNCATED: &str = "…";
 
 pub const FILE_COLOR_DEFAULT: Color = Color::Cyan;
 pub const DIR_COLOR_DEFAULT: Color = Color::Blue;
@@ -20,6 +21,14 @@ pub const MATCHED_COLORS_DEFAULT: [Color; 3] = [Color::Green, Color::Magenta, Co
 pub const SELECTED_BG_DEFAULT: Color = Color::DarkGrey;
 pub const FILTER_HIGHLIGHT_DEFAULT: Color = Color::Black;
 
+pub const CHAR_VERTICAL_DEFAULT: char = '│';
+pub const CHAR_HORIZONTAL_DEFAULT: char = '─';
+pub const CHAR_TOP_LEFT_DEFAULT: char = '╭';
+pub const CHAR_TOP_RIGHT_DEFAULT: char = '╮';
+pub const CHAR_BOTTOM_LEFT_DEFAULT: char = '╰';
+pub const CHAR_BOTTOM_RIGHT_DEFAULT: char = '╯';
+pub const CHAR_TEE_DEFAULT: char = '├';
+
 pub struct DisplayRepeater<T>(T, usize);
 impl<T: Display> Display for DisplayRepeater<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -43,6 +52,58 @@ pub fn error_prefix(bold: bool, colors: bool) -> String {
     }
 }
 
+pub fn css_color(color: Color) -> String {
+    const NAMED: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (128, 0, 0),
+        (0, 128, 0),
+        (128, 128, 0),
+        (0, 0, 128),
+        (128, 0, 128),
+        (0, 128, 128),
+        (192, 192, 192),
+        (128, 128, 128),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (0, 0, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let (r, g, b) = match color {
+        Color::Reset => return "inherit".to_string(),
+        Color::Black => NAMED[0],
+        Color::DarkRed => NAMED[1],
+        Color::DarkGreen => NAMED[2],
+        Color::DarkYellow => NAMED[3],
+        Color::DarkBlue => NAMED[4],
+        Color::DarkMagenta => NAMED[5],
+        Color::DarkCyan => NAMED[6],
+        Color::Grey => NAMED[7],
+        Color::DarkGrey => NAMED[8],
+        Color::Red => NAMED[9],
+        Color::Green => NAMED[10],
+        Color::Yellow => NAMED[11],
+        Color::Blue => NAMED[12],
+        Color::Magenta => NAMED[13],
+        Color::Cyan => NAMED[14],
+        Color::White => NAMED[15],
+        Color::Rgb { r, g, b } => (r, g, b),
+        Color::AnsiValue(n) if n < 16 => NAMED[n as usize],
+        Color::AnsiValue(n) if n < 232 => {
+            let n = (n - 16) as usize;
+            (LEVELS[n / 36], LEVELS[n / 6 % 6], LEVELS[n % 6])
+        }
+        Color::AnsiValue(n) => {
+            let v = 8 + 10 * (n - 232);
+            (v, v, v)
+        }
+    };
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
 pub fn style_with<D: Display>(orig: D, color: Color, config: &Config) -> StyledContent<D> {
     let mut styled = style(orig);
     if config.with_colors {
@@ -86,3 +147,17 @@ pub fn style_on<D: Display>(orig: D, bg: Color, config: &Config) -> StyledConten
     }
     s
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_css_color() {
+        assert_eq!(css_color(Color::Red), "#ff0000");
+        assert_eq!(css_color(Color::Rgb { r: 1, g: 2, b: 3 }), "#010203");
+        assert_eq!(css_color(Color::AnsiValue(4)), "#000080");
+        assert_eq!(css_color(Color::AnsiValue(196)), "#ff0000");
+        assert_eq!(css_color(Color::AnsiValue(244)), "#808080");
+    }
+}
@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: MIT
+
+use crate::{errors::Message, mes};
+use crossterm::style::Color;
+use std::path::Path;
+
+pub type Spans = Vec<(usize, Color)>;
+
+#[cfg(feature = "syntax")]
+mod bundled {
+    use std::sync::OnceLock;
+    use syntect::{highlighting::ThemeSet, parsing::SyntaxSet};
+
+    pub fn syntaxes() -> &'static SyntaxSet {
+        static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+        SYNTAXES.get_or_init(SyntaxSet::load_defaults_nonewlines)
+    }
+
+    pub fn themes() -> &'static ThemeSet {
+        static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+        THEMES.get_or_init(ThemeSet::load_defaults)
+    }
+}
+
+#[cfg(feature = "syntax")]
+pub fn check_theme(name: &str) -> Result<(), Message> {
+    let themes = &bundled::themes().themes;
+    if themes.contains_key(name) {
+        return Ok(());
+    }
+    let mut names: Vec<&str> = themes.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    Err(mes!(
+        "unknown syntax theme `{}`, expected one of {}",
+        name,
+        names.join(", ")
+    ))
+}
+
+#[cfg(not(feature = "syntax"))]
+pub fn check_theme(_name: &str) -> Result<(), Message> {
+    Err(mes!("syntax highlighting is not available in this build"))
+}
+
+pub struct Highlighter {
+    #[cfg(feature = "syntax")]
+    lines: Option<syntect::easy::HighlightLines<'static>>,
+}
+
+impl Highlighter {
+    #[cfg(feature = "syntax")]
+    pub fn new(path: &Path, theme: &str) -> Self {
+        let syntaxes = bundled::syntaxes();
+        let lines = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|e| syntaxes.find_syntax_by_extension(e))
+            .zip(bundled::themes().themes.get(theme))
+            .map(|(s, t)| syntect::easy::HighlightLines::new(s, t));
+        Highlighter { lines }
+    }
+
+    #[cfg(not(feature = "syntax"))]
+    pub fn new(_path: &Path, _theme: &str) -> Self {
+        Highlighter {}
+    }
+
+    #[cfg(feature = "syntax")]
+    pub fn line(&mut self, content: &[u8]) -> Spans {
+        let (Some(lines), Ok(text)) = (&mut self.lines, std::str::from_utf8(content)) else {
+            return Spans::new();
+        };
+        let Ok(regions) = lines.highlight_line(text, bundled::syntaxes()) else {
+            return Spans::new();
+        };
+        let mut pos = 0;
+        regions
+            .into_iter()
+            .map(|(style, s)| {
+                let c = style.foreground;
+                let span = (
+                    pos,
+                    Color::Rgb {
+                        r: c.r,
+                        g: c.g,
+                        b: c.b,
+                    },
+                );
+                pos += s.len();
+                span
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "syntax"))]
+    pub fn line(&mut self, _content: &[u8]) -> Spans {
+        Spans::new()
+    }
+}
+
+pub fn clip(spans: &[(usize, Color)], start: usize, end: usize) -> Spans {
+    let first = spans.partition_point(|&(p, _)| p <= start);
+    spans[first.saturating_sub(1)..]
+        .iter()
+        .take_while(|&&(p, _)| p < end)
+        .map(|&(p, c)| (p.saturating_sub(start), c))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clip() {
+        let spans = vec![(0, Color::Red), (4, Color::Blue), (8, Color::Green)];
+        assert_eq!(
+            clip(&spans, 5, 9),
+            vec![(0, Color::Blue), (3, Color::Green)]
+        );
+        assert_eq!(clip(&spans, 0, 4), vec![(0, Color::Red)]);
+        assert!(clip(&[], 0, 4).is_empty());
+    }
+
+    #[cfg(feature = "syntax")]
+    #[test]
+    fn test_highlighter() {
+        let mut h = Highlighter::new(Path::new("main.rs"), "base16-ocean.dark");
+        let spans = h.line(b"fn main() {}");
+        assert!(spans.len() > 1);
+        assert_eq!(spans[0].0, 0);
+        assert!(
+            Highlighter::new(Path::new("notes"), "base16-ocean.dark")
+                .line(b"fn")
+                .is_empty()
+        );
+        assert!(check_theme("base16-ocean.dark").is_ok());
+        assert!(check_theme("missing").is_err());
+    }
+}
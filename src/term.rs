@@ -5,12 +5,22 @@ use crossterm::{
     terminal::{self, ClearType},
 };
 use std::{
-    io::{self, StdoutLock, Write},
+    io::{self, IsTerminal, StdoutLock, Write},
     panic,
     sync::atomic::{AtomicU16, Ordering},
 };
 
 pub static TERM_WIDTH: AtomicU16 = AtomicU16::new(0);
+const PLAIN_WIDTH: u16 = 80;
+
+pub fn set_plain_width() {
+    let width = if io::stdout().is_terminal() {
+        terminal::size().map_or(PLAIN_WIDTH, |(w, _)| w)
+    } else {
+        PLAIN_WIDTH
+    };
+    TERM_WIDTH.store(width, Ordering::SeqCst);
+}
 
 pub struct Term<'a> {
     pub height: u16,
@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    args::{ColorError, parse_color},
+    config::{Colors, process_path},
+    errors::Message,
+    mes, style,
+};
+use crossterm::style::Color;
+
+pub const DARK: &str = "dark";
+pub const LIGHT: &str = "light";
+
+#[derive(Clone)]
+pub struct Branches {
+    pub v: char,
+    pub h: char,
+    pub tl: char,
+    pub tr: char,
+    pub bl: char,
+    pub br: char,
+    pub tee: char,
+}
+
+#[derive(Clone)]
+pub struct Theme {
+    pub colors: Colors,
+    pub branches: Branches,
+}
+
+const ROUNDED: Branches = Branches {
+    v: style::CHAR_VERTICAL_DEFAULT,
+    h: style::CHAR_HORIZONTAL_DEFAULT,
+    tl: style::CHAR_TOP_LEFT_DEFAULT,
+    tr: style::CHAR_TOP_RIGHT_DEFAULT,
+    bl: style::CHAR_BOTTOM_LEFT_DEFAULT,
+    br: style::CHAR_BOTTOM_RIGHT_DEFAULT,
+    tee: style::CHAR_TEE_DEFAULT,
+};
+
+pub fn dark() -> Theme {
+    Theme {
+        colors: Colors {
+            file: style::FILE_COLOR_DEFAULT,
+            dir: style::DIR_COLOR_DEFAULT,
+            line_number: style::LINE_NUMBER_COLOR_DEFAULT,
+            text: None,
+            branch: None,
+            selected_indicator: None,
+            selected_bg: style::SELECTED_BG_DEFAULT,
+            matches: style::MATCHED_COLORS_DEFAULT.to_vec(),
+            filter_highlight: style::FILTER_HIGHLIGHT_DEFAULT,
+        },
+        branches: ROUNDED,
+    }
+}
+
+pub fn light() -> Theme {
+    Theme {
+        colors: Colors {
+            file: Color::DarkCyan,
+            dir: Color::DarkBlue,
+            line_number: Color::DarkYellow,
+            text: None,
+            branch: Some(Color::DarkGrey),
+            selected_indicator: Some(Color::DarkBlue),
+            selected_bg: Color::Grey,
+            matches: vec![Color::DarkGreen, Color::DarkMagenta, Color::DarkRed],
+            filter_highlight: Color::Yellow,
+        },
+        branches: ROUNDED,
+    }
+}
+
+fn color(key: &str, value: &str) -> Result<Color, Message> {
+    parse_color(value).map(|c| c.get()).map_err(|e| match e {
+        ColorError::Invalid(kind, inner) => {
+            mes!("invalid {} value `{}` for `{}` in theme", kind, inner, key)
+        }
+        ColorError::Unknown(s) => mes!("unknown color `{}` for `{}` in theme", s, key),
+    })
+}
+
+fn optional_color(key: &str, value: &str) -> Result<Option<Color>, Message> {
+    if value.eq_ignore_ascii_case("none") {
+        Ok(None)
+    } else {
+        color(key, value).map(Some)
+    }
+}
+
+fn branch_char(key: &str, value: &str) -> Result<char, Message> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(mes!("`{}` in theme must be a single character", key)),
+    }
+}
+
+fn parse(data: &str) -> Result<Theme, Message> {
+    let mut entries = Vec::new();
+    for (i, line) in data.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| mes!("expected `key = value` on line {} of theme", i + 1))?;
+        entries.push((key.trim(), value.trim()));
+    }
+    let mut theme = match entries.iter().find(|(k, _)| *k == "base") {
+        Some((_, v)) if *v == LIGHT => light(),
+        Some((_, v)) if *v == DARK => dark(),
+        Some((_, v)) => return Err(mes!("unknown base theme `{}`", v)),
+        None => dark(),
+    };
+    let (c, b) = (&mut theme.colors, &mut theme.branches);
+    for (key, value) in entries {
+        match key {
+            "base" => {}
+            "file" => c.file = color(key, value)?,
+            "dir" => c.dir = color(key, value)?,
+            "line_number" => c.line_number = color(key, value)?,
+            "text" => c.text = optional_color(key, value)?,
+            "branch" => c.branch = optional_color(key, value)?,
+            "selected_indicator" => c.selected_indicator = optional_color(key, value)?,
+            "selected_bg" => c.selected_bg = color(key, value)?,
+            "filter_highlight" => c.filter_highlight = color(key, value)?,
+            "matches" => {
+                c.matches = value
+                    .split(',')
+                    .map(|v| color(key, v))
+                    .collect::<Result<_, _>>()?
+            }
+            "vertical" => b.v = branch_char(key, value)?,
+            "horizontal" => b.h = branch_char(key, value)?,
+            "top_left" => b.tl = branch_char(key, value)?,
+            "top_right" => b.tr = branch_char(key, value)?,
+            "bottom_left" => b.bl = branch_char(key, value)?,
+            "bottom_right" => b.br = branch_char(key, value)?,
+            "tee" => b.tee = branch_char(key, value)?,
+            _ => return Err(mes!("unknown theme key `{}`", key)),
+        }
+    }
+    Ok(theme)
+}
+
+pub fn load(name: Option<&str>) -> Result<Theme, Message> {
+    match name {
+        None | Some(DARK) => Ok(dark()),
+        Some(LIGHT) => Ok(light()),
+        Some(p) => {
+            let path = process_path(std::path::Path::new(p), false)?;
+            if !path.is_file() {
+                return Err(mes!(
+                    "unknown theme `{}`, expected {}, {} or a theme file",
+                    p,
+                    DARK,
+                    LIGHT
+                ));
+            }
+            let data = std::fs::read_to_string(&path)
+                .map_err(|e| mes!("failed to read theme `{}`: {}", path.display(), e))?;
+            parse(&data)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let theme = parse(
+            "# comment\nbase = light\nfile = rgb(1.2.3)\ntext = red\nbranch = none\nmatches = red, ansi(4)\ntee = +\n",
+        )
+        .unwrap();
+        assert_eq!(theme.colors.file, Color::Rgb { r: 1, g: 2, b: 3 });
+        assert_eq!(theme.colors.text, Some(Color::Red));
+        assert_eq!(theme.colors.branch, None);
+        assert_eq!(theme.colors.matches, vec![Color::Red, Color::AnsiValue(4)]);
+        assert_eq!(theme.colors.dir, Color::DarkBlue);
+        assert_eq!(theme.branches.tee, '+');
+        assert_eq!(theme.branches.v, '│');
+        assert!(parse("file = nope").is_err());
+        assert!(parse("colour = red").is_err());
+        assert!(parse("tee = ab").is_err());
+        assert!(parse("file").is_err());
+    }
+}
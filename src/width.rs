@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: MIT
+
+use crate::match_system::Match;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+pub fn width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
+}
+
+pub fn span(raw: &[u8]) -> usize {
+    raw.utf8_chunks()
+        .map(|c| width(c.valid()) + usize::from(!c.invalid().is_empty()))
+        .sum()
+}
+
+pub fn boundaries(raw: &[u8]) -> Vec<(usize, usize)> {
+    let mut bounds = vec![(0, 0)];
+    let (mut pos, mut col) = (0, 0);
+    for chunk in raw.utf8_chunks() {
+        for g in chunk.valid().graphemes(true) {
+            pos += g.len();
+            col += width(g);
+            bounds.push((pos, col));
+        }
+        if !chunk.invalid().is_empty() {
+            pos += chunk.invalid().len();
+            col += 1;
+            bounds.push((pos, col));
+        }
+    }
+    bounds
+}
+
+pub fn column_before(bounds: &[(usize, usize)], byte: usize) -> usize {
+    bounds[bounds.partition_point(|&(b, _)| b <= byte).max(1) - 1].1
+}
+
+pub fn column_after(bounds: &[(usize, usize)], byte: usize) -> usize {
+    let i = bounds.partition_point(|&(b, _)| b < byte);
+    bounds[i.min(bounds.len() - 1)].1
+}
+
+pub fn last_within(bounds: &[(usize, usize)], col: usize) -> (usize, usize) {
+    bounds[bounds.partition_point(|&(_, c)| c <= col).max(1) - 1]
+}
+
+pub fn first_from(bounds: &[(usize, usize)], col: usize) -> (usize, usize) {
+    let i = bounds.partition_point(|&(_, c)| c < col);
+    bounds[i.min(bounds.len() - 1)]
+}
+
+pub fn expand_tabs(
+    raw: &[u8],
+    matches: &[Match],
+    cut: usize,
+    tab_width: usize,
+) -> Option<(Vec<u8>, Vec<Match>)> {
+    if !raw.contains(&b'\t') {
+        return None;
+    }
+    let mut segments = raw.split(|&b| b == b'\t');
+    let first = segments.next().unwrap_or_default();
+    let mut out = first.to_vec();
+    let (mut old, mut col) = (first.len(), span(first));
+    let mut shifts = Vec::new();
+    for segment in segments {
+        let n = tab_width - col % tab_width;
+        out.resize(out.len() + n, b' ');
+        old += 1;
+        col += n;
+        shifts.push((old, out.len() - old));
+        out.extend_from_slice(segment);
+        old += segment.len();
+        col += span(segment);
+    }
+    let shift = |pos: usize| {
+        let pos = pos.saturating_sub(cut).min(raw.len());
+        pos + shifts
+            .iter()
+            .rev()
+            .find(|&&(p, _)| p <= pos)
+            .map_or(0, |&(_, s)| s)
+    };
+    let matches = matches
+        .iter()
+        .map(|m| Match::new(m.regexp_id, shift(m.start), shift(m.end)))
+        .collect();
+    Some((out, matches))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boundaries() {
+        assert_eq!(
+            boundaries("a日e\u{301}".as_bytes()),
+            vec![(0, 0), (1, 1), (4, 3), (7, 4)]
+        );
+        assert_eq!(boundaries(b"a\xffb"), vec![(0, 0), (1, 1), (2, 2), (3, 3)]);
+        let bounds = boundaries("日本".as_bytes());
+        assert_eq!(column_before(&bounds, 4), 2);
+        assert_eq!(column_after(&bounds, 4), 4);
+        assert_eq!(last_within(&bounds, 3), (3, 2));
+        assert_eq!(first_from(&bounds, 3), (6, 4));
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        assert!(expand_tabs(b"no tabs", &[], 0, 4).is_none());
+        let (out, matches) = expand_tabs(
+            b"a\tbc\td",
+            &[Match::new(0, 2, 4), Match::new(1, 5, 6)],
+            0,
+            4,
+        )
+        .unwrap();
+        assert_eq!(out, b"a   bc  d");
+        assert_eq!(matches[0].start, 4);
+        assert_eq!(matches[0].end, 6);
+        assert_eq!(matches[1].start, 8);
+        assert_eq!(matches[1].end, 9);
+        let (out, _) = expand_tabs("日\tx".as_bytes(), &[], 0, 4).unwrap();
+        assert_eq!(out, "日  x".as_bytes());
+        let (_, matches) = expand_tabs(b"\tx", &[Match::new(0, 3, 4)], 2, 4).unwrap();
+        assert_eq!((matches[0].start, matches[0].end), (4, 5));
+    }
+}
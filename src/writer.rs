@@ -1,15 +1,23 @@
 // SPDX-License-Identifier: MIT
 
 use crate::{
+    args::{OverviewBy, Placeholder, Template, TemplatePart},
     config::Config,
     errors::Message,
-    match_system::{Directory, File, Match, Matches},
-    mes, style,
+    hyperlink::Hyperlink,
+    match_system::{Directory, File, Line, Match, Matches, Totals, collect_files},
+    mes,
+    meta::Meta,
+    style,
+    syntax::{self, Highlighter, Spans},
     term::{TERM_WIDTH, Term},
+    width,
 };
 use core::fmt::{self, Display};
 use crossterm::style::Color;
 use std::{
+    borrow::Cow,
+    collections::HashMap,
     io::{self, Write},
     path::{Path, PathBuf},
     sync::{Arc, atomic::Ordering},
@@ -51,6 +59,7 @@ enum HighlightEvent<'a> {
     RegexEnd,
     FilterStart,
     FilterEnd,
+    Text(Color),
 }
 
 impl HighlightEvent<'_> {
@@ -58,8 +67,9 @@ impl HighlightEvent<'_> {
         match self {
             Self::RegexEnd => 0,
             Self::FilterEnd => 1,
-            Self::FilterStart => 2,
-            Self::RegexStart(_) => 3,
+            Self::Text(_) => 2,
+            Self::FilterStart => 3,
+            Self::RegexStart(_) => 4,
         }
     }
 }
@@ -122,7 +132,7 @@ fn write_content_with_highlights(
     raw: &[u8],
     matches: &[Match],
     cut: usize,
-    text_fg: Option<Color>,
+    text: &[(usize, Color)],
     filter: &str,
     config: &Config,
 ) -> fmt::Result {
@@ -149,11 +159,16 @@ fn write_content_with_highlights(
         events.push((ss, HighlightEvent::FilterStart));
         events.push((se, HighlightEvent::FilterEnd));
     }
+    for &(start, c) in text {
+        let ts = decoded_pos(&offsets, start.saturating_sub(cut).min(raw.len()));
+        events.push((ts, HighlightEvent::Text(c)));
+    }
     events.sort_by_key(|(pos, e)| (*pos, e.priority()));
 
     let mut pos = 0;
     let mut regex_m: Option<&Match> = None;
     let mut in_filter = false;
+    let mut text_fg = None;
     for (event_pos, event_type) in &events {
         if *event_pos > pos {
             write_segment(
@@ -171,6 +186,7 @@ fn write_content_with_highlights(
             HighlightEvent::RegexEnd => regex_m = None,
             HighlightEvent::FilterStart => in_filter = true,
             HighlightEvent::FilterEnd => in_filter = false,
+            HighlightEvent::Text(c) => text_fg = Some(*c),
         }
     }
     if pos < content.len() {
@@ -198,6 +214,9 @@ pub trait Entry: Display {
     fn depth(&self) -> usize;
     fn is_path(&self) -> bool;
     fn filter_text(&self) -> String;
+    fn is_wrap(&self) -> bool {
+        false
+    }
 }
 
 pub struct WithFilter<'a> {
@@ -211,10 +230,26 @@ impl Display for WithFilter<'_> {
     }
 }
 
+fn path_color(path: &Path, dir: bool, config: &Config) -> Color {
+    config
+        .ls_colors
+        .as_ref()
+        .and_then(|l| l.color(path))
+        .unwrap_or(if dir {
+            config.colors.dir
+        } else {
+            config.colors.file
+        })
+}
+
 struct PathDisplay {
     prefix: Option<Vec<PrefixComponent>>,
     name: String,
+    matches: Vec<Match>,
+    summary: Option<String>,
     color: Color,
+    icon: Option<String>,
+    columns: Option<String>,
     path: PathBuf,
     linked: Option<String>,
     count: usize,
@@ -232,12 +267,12 @@ impl PathDisplay {
         dir: bool,
         config: Arc<Config>,
     ) -> Result<PathDisplay, Message> {
-        let color = if dir {
-            config.colors.dir
-        } else {
-            config.colors.file
-        };
+        let color = path_color(path, dir, &config);
         let name = path_name(path)?.to_owned();
+        let icon = config
+            .icons
+            .as_ref()
+            .map(|i| i.icon(&name, dir).to_string());
         let linked = linked
             .as_ref()
             .map(|l| path_name(l).map(|s| s.to_owned()))
@@ -245,7 +280,11 @@ impl PathDisplay {
         Ok(PathDisplay {
             prefix,
             name,
+            matches: Vec::new(),
+            summary: None,
             color,
+            icon,
+            columns: None,
             path: path.to_path_buf(),
             linked,
             count,
@@ -253,6 +292,22 @@ impl PathDisplay {
             config,
         })
     }
+    fn with_matches(mut self, matches: &[Match]) -> Self {
+        self.matches = matches.to_vec();
+        self
+    }
+    fn with_name(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
+    fn with_summary(mut self, summary: Option<String>) -> Self {
+        self.summary = summary;
+        self
+    }
+    fn with_meta(mut self, meta: Option<Meta>) -> Self {
+        self.columns = meta.map(|m| m.columns(self.config.search.permissions));
+        self
+    }
 }
 
 impl Entry for PathDisplay {
@@ -260,15 +315,7 @@ impl Entry for PathDisplay {
         if let Some(p) = &self.prefix {
             write_prefix(f, p, &self.config)?;
         }
-        write_path(
-            f,
-            &self.name,
-            self.color,
-            self.linked.as_deref(),
-            self.count,
-            filter,
-            &self.config,
-        )?;
+        write_path(f, self, filter)?;
         if self.new_line {
             writeln!(f)?;
         }
@@ -300,29 +347,52 @@ impl Display for PathDisplay {
     }
 }
 
-fn write_path(
-    f: &mut fmt::Formatter,
-    name: &str,
-    color: Color,
-    linked: Option<&str>,
-    count: usize,
-    filter: &str,
-    config: &Config,
-) -> fmt::Result {
+fn write_path(f: &mut fmt::Formatter, path: &PathDisplay, filter: &str) -> fmt::Result {
+    let config = &path.config;
+    if let Some(c) = &path.columns {
+        write!(f, "{} ", c)?;
+    }
+    write_icon(f, path.icon.as_deref(), path.color, config)?;
+    if let Some(link) = &config.hyperlink {
+        link.start(f, &path.path, None)?;
+    }
     if cfg!(feature = "test") {
-        write!(f, "[ps]{}[pe]", name)?;
-        if let Some(l) = linked {
+        f.write_str("[ps]")?;
+        write_test_markers(f, path.name.as_bytes(), &path.matches, 0, None, config)?;
+        f.write_str("[pe]")?;
+        if let Some(l) = &path.linked {
             write!(f, " -> [ps]{}[pe]", l)?;
         }
     } else {
-        write_content_with_highlights(f, name.as_bytes(), &[], 0, Some(color), filter, config)?;
-        if let Some(l) = linked {
+        write_content_with_highlights(
+            f,
+            path.name.as_bytes(),
+            &path.matches,
+            0,
+            &[(0, path.color)],
+            filter,
+            config,
+        )?;
+        if let Some(l) = &path.linked {
             f.write_str(" -> ")?;
-            write_content_with_highlights(f, l.as_bytes(), &[], 0, Some(color), filter, config)?;
+            write_content_with_highlights(
+                f,
+                l.as_bytes(),
+                &[],
+                0,
+                &[(0, path.color)],
+                filter,
+                config,
+            )?;
         }
     }
-    if config.search.count && count > 0 {
-        write!(f, ": {}", count)?;
+    if config.hyperlink.is_some() {
+        Hyperlink::end(f)?;
+    }
+    if let Some(s) = &path.summary {
+        write!(f, ": {}", s)?;
+    } else if config.search.count && path.count > 0 {
+        write!(f, ": {}", path.count)?;
     }
     if config.with_colors || config.with_bold {
         write!(f, "{}", style::RESET)?;
@@ -330,6 +400,30 @@ fn write_path(
     Ok(())
 }
 
+fn file_icon(path: &Path, config: &Config) -> Option<String> {
+    let name = path.file_name()?.to_string_lossy();
+    config
+        .icons
+        .as_ref()
+        .map(|i| i.icon(&name, false).to_string())
+}
+
+fn icon_width(icon: Option<&str>) -> usize {
+    icon.map_or(0, |i| width::width(i) + 1)
+}
+
+fn write_icon(
+    f: &mut fmt::Formatter,
+    icon: Option<&str>,
+    color: Color,
+    config: &Config,
+) -> fmt::Result {
+    match icon {
+        Some(i) => write!(f, "{} ", style::style_with(i, color, config)),
+        None => Ok(()),
+    }
+}
+
 fn write_prefix(
     f: &mut fmt::Formatter,
     prefix_components: &[PrefixComponent],
@@ -357,12 +451,185 @@ struct LineDisplay {
     content: Vec<u8>,
     path: PathBuf,
     matches: Vec<Match>,
+    syntax: Spans,
     line_num: usize,
     context_offset: Option<isize>,
     new_line: bool,
     config: Arc<Config>,
 }
 
+fn truncate_window(raw: &[u8], matches: &[Match], cut: usize, cap: usize) -> (usize, usize) {
+    let bounds = width::boundaries(raw);
+    let total = bounds[bounds.len() - 1].1;
+    let first = matches.iter().find(|m| m.start < m.end).map(|m| {
+        (
+            width::column_before(&bounds, m.start.saturating_sub(cut)),
+            width::column_after(&bounds, m.end.saturating_sub(cut)),
+        )
+    });
+    let ellipsis = width::width(style::TRUNCATED);
+    let tail_room = cap.saturating_sub(ellipsis).max(1);
+    if total <= cap {
+        return (0, raw.len());
+    }
+    let head = (0, width::last_within(&bounds, tail_room).0);
+    let Some((m_start, m_end)) = first.filter(|&(_, m_end)| m_end > tail_room) else {
+        return head;
+    };
+    let room = cap.saturating_sub(2 * ellipsis).max(1);
+    let mut start = m_start.saturating_sub(room.saturating_sub(m_end - m_start) / 2);
+    if total - start <= tail_room {
+        start = total - tail_room;
+    }
+    let (start_byte, start) = width::first_from(&bounds, start);
+    if start_byte == 0 {
+        return head;
+    }
+    let end = if total - start <= tail_room {
+        raw.len()
+    } else {
+        width::last_within(&bounds, start + room).0
+    };
+    (start_byte, end)
+}
+
+struct LineText<'a> {
+    content: &'a [u8],
+    matches: &'a [Match],
+    syntax: &'a [(usize, Color)],
+}
+
+fn text_colors<'a>(syntax: &'a [(usize, Color)], config: &Config) -> Cow<'a, [(usize, Color)]> {
+    if syntax.is_empty() || !config.with_colors {
+        Cow::Owned(config.colors.text.map(|c| (0, c)).into_iter().collect())
+    } else {
+        Cow::Borrowed(syntax)
+    }
+}
+
+fn write_line_content(
+    f: &mut fmt::Formatter,
+    text: LineText,
+    is_context: bool,
+    used_width: usize,
+    filter: &str,
+    config: &Config,
+) -> fmt::Result {
+    let mut raw: &[u8] = text.content;
+    let matches = text.matches;
+
+    let cut = if config.search.trim {
+        let n = raw.iter().take_while(|&&b| b.is_ascii_whitespace()).count();
+        raw = &raw[n..];
+        n
+    } else {
+        0
+    };
+
+    let expanded = width::expand_tabs(raw, matches, cut, config.tab_width);
+    let (mut raw, matches, cut) = match &expanded {
+        Some((r, m)) => (r.as_slice(), m.as_slice(), 0),
+        None => (raw, matches, cut),
+    };
+
+    let cap = if config.search.wrap {
+        None
+    } else if config.select || config.menu {
+        let term_width = TERM_WIDTH.load(Ordering::SeqCst) as usize;
+        let indicator_len = width::width(&config.chars.selected_indicator);
+        let content_width = term_width.saturating_sub(indicator_len + used_width);
+        Some(
+            config
+                .search
+                .max_length
+                .map_or(content_width, |m| m.min(content_width)),
+        )
+    } else {
+        config.search.max_length
+    };
+    let (start, end) = cap.map_or((0, raw.len()), |cap| {
+        truncate_window(raw, matches, cut, cap)
+    });
+    let (leading, trailing) = (start > 0, end < raw.len());
+    raw = &raw[start..end];
+    let cut = cut + start;
+
+    if is_context && config.with_colors {
+        write!(f, "{}", style::DIM)?;
+    }
+
+    if leading {
+        f.write_str(style::TRUNCATED)?;
+    }
+    if cfg!(feature = "test") {
+        write_test_markers(f, raw, matches, cut, config.colors.text, config)?;
+    } else {
+        let colors = text_colors(text.syntax, config);
+        write_content_with_highlights(f, raw, matches, cut, &colors, filter, config)?;
+    }
+    if trailing {
+        f.write_str(style::TRUNCATED)?;
+    }
+
+    if is_context && config.with_colors {
+        write!(f, "{}", style::RESET)?;
+    }
+    Ok(())
+}
+
+fn write_test_markers(
+    f: &mut fmt::Formatter,
+    raw: &[u8],
+    matches: &[Match],
+    cut: usize,
+    text_fg: Option<Color>,
+    config: &Config,
+) -> fmt::Result {
+    let content_cow = String::from_utf8_lossy(raw);
+    let content: &str = &content_cow;
+    let offsets = byte_offsets(raw);
+    let write_text = |f: &mut fmt::Formatter, text: &str| match text_fg {
+        Some(c) => write!(f, "{}", style::style_with(text, c, config)),
+        None => f.write_str(text),
+    };
+    let mut last = 0;
+    for m in matches.iter() {
+        if m.start >= m.end {
+            continue;
+        }
+        let m_start_raw = m.start.saturating_sub(cut);
+        let m_end_raw = m.end.saturating_sub(cut).min(raw.len());
+        let m_start = decoded_pos(&offsets, m_start_raw);
+        let m_end = decoded_pos(&offsets, m_end_raw).min(content.len());
+        if m_start >= content.len() {
+            continue;
+        }
+        if last < m_start {
+            write_text(f, &content[last..m_start])?;
+        }
+        let styled = style::match_substring(&content[m_start..m_end], m.regexp_id, config);
+        write!(f, "[m{}s]{}[m{}e]", m.regexp_id, styled, m.regexp_id)?;
+        last = m_end;
+    }
+    if last < content.len() {
+        write_text(f, &content[last..])?;
+    }
+    Ok(())
+}
+
+fn trimmed_text(content: &[u8], config: &Config) -> String {
+    let raw: &[u8] = if config.search.trim {
+        let n = content
+            .iter()
+            .take_while(|&&b| b.is_ascii_whitespace())
+            .count();
+        &content[n..]
+    } else {
+        content
+    };
+    String::from_utf8_lossy(raw).into_owned()
+}
+
 impl Entry for LineDisplay {
     fn render(&self, f: &mut fmt::Formatter, filter: &str) -> fmt::Result {
         write_prefix(f, &self.prefix, &self.config)?;
@@ -370,42 +637,15 @@ impl Entry for LineDisplay {
             write!(f, "{}", style::RESET)?;
         }
 
-        let mut raw: &[u8] = &self.content;
-
-        let cut = if self.config.search.trim {
-            let n = raw.iter().take_while(|&&b| b.is_ascii_whitespace()).count();
-            raw = &raw[n..];
-            n
-        } else {
-            0
-        };
-
-        let line_num = if self.config.search.line_number {
-            Some(
-                self.context_offset
-                    .map_or_else(|| format!("{}: ", self.line_num), |o| format!("{:+}: ", o)),
-            )
-        } else {
-            None
-        };
+        let line_num = self
+            .config
+            .search
+            .line_number
+            .then(|| line_num_label(self.line_num, self.context_offset));
 
-        if self.config.select || self.config.menu {
-            let term_width = TERM_WIDTH.load(Ordering::SeqCst) as usize;
-            let indicator_len = self.config.chars.selected_indicator.chars().count();
-            let branch_len = self.config.branch_len * self.prefix.len();
-            let line_num_len = line_num.as_ref().map_or(0, |n| n.len());
-            let content_width =
-                term_width.saturating_sub(indicator_len + branch_len + line_num_len);
-            let cap = self
-                .config
-                .search
-                .max_length
-                .map_or(content_width, |m| m.min(content_width));
-            raw = &raw[..cap.min(raw.len())];
-        } else if let Some(max) = self.config.search.max_length {
-            raw = &raw[..max.min(raw.len())];
+        if let Some(link) = &self.config.hyperlink {
+            link.start(f, &self.path, Some(self.line_num))?;
         }
-
         if let Some(n) = &line_num {
             write!(
                 f,
@@ -414,62 +654,102 @@ impl Entry for LineDisplay {
             )?;
         }
 
-        if self.context_offset.is_some() && self.config.with_colors {
-            write!(f, "{}", style::DIM)?;
+        write_line_content(
+            f,
+            LineText {
+                content: &self.content,
+                matches: &self.matches,
+                syntax: &self.syntax,
+            },
+            self.context_offset.is_some(),
+            self.config.branch_len * self.prefix.len() + line_num.as_ref().map_or(0, |n| n.len()),
+            filter,
+            &self.config,
+        )?;
+        if self.config.hyperlink.is_some() {
+            Hyperlink::end(f)?;
+        }
+
+        if self.new_line {
+            writeln!(f)?;
         }
+        Ok(())
+    }
+    fn open_info(&self) -> Result<OpenInfo<'_>, Message> {
+        Ok(OpenInfo {
+            path: &self.path,
+            line: Some(self.line_num),
+        })
+    }
+    fn depth(&self) -> usize {
+        self.prefix.len()
+    }
+    fn is_path(&self) -> bool {
+        false
+    }
+    fn filter_text(&self) -> String {
+        trimmed_text(&self.content, &self.config)
+    }
+}
+
+impl Display for LineDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, "")
+    }
+}
+
+struct WrapDisplay {
+    prefix: Vec<PrefixComponent>,
+    indent: usize,
+    content: Vec<u8>,
+    path: PathBuf,
+    matches: Vec<Match>,
+    syntax: Spans,
+    line_num: usize,
+    is_context: bool,
+    new_line: bool,
+    config: Arc<Config>,
+}
 
+impl Entry for WrapDisplay {
+    fn render(&self, f: &mut fmt::Formatter, filter: &str) -> fmt::Result {
+        write_prefix(f, &self.prefix, &self.config)?;
+        if self.config.with_colors || self.config.with_bold {
+            write!(f, "{}", style::RESET)?;
+        }
+        write!(f, "{}", style::repeat(' ', self.indent))?;
+        if let Some(link) = &self.config.hyperlink {
+            link.start(f, &self.path, Some(self.line_num))?;
+        }
+        if self.is_context && self.config.with_colors {
+            write!(f, "{}", style::DIM)?;
+        }
         if cfg!(feature = "test") {
-            let content_cow = String::from_utf8_lossy(raw);
-            let content: &str = &content_cow;
-            let offsets = byte_offsets(raw);
-            let mut last = 0;
-            for m in self.matches.iter() {
-                if m.start >= m.end {
-                    continue;
-                }
-                let m_start_raw = m.start.saturating_sub(cut);
-                let m_end_raw = m.end.saturating_sub(cut).min(raw.len());
-                let m_start = decoded_pos(&offsets, m_start_raw);
-                let m_end = decoded_pos(&offsets, m_end_raw).min(content.len());
-                if m_start >= content.len() {
-                    continue;
-                }
-                if last < m_start {
-                    let text = &content[last..m_start];
-                    if let Some(c) = self.config.colors.text {
-                        write!(f, "{}", style::style_with(text, c, &self.config))?;
-                    } else {
-                        f.write_str(text)?;
-                    }
-                }
-                let styled =
-                    style::match_substring(&content[m_start..m_end], m.regexp_id, &self.config);
-                write!(f, "[m{}s]{}[m{}e]", m.regexp_id, styled, m.regexp_id)?;
-                last = m_end;
-            }
-            if last < content.len() {
-                let text = &content[last..];
-                if let Some(c) = self.config.colors.text {
-                    write!(f, "{}", style::style_with(text, c, &self.config))?;
-                } else {
-                    f.write_str(text)?;
-                }
-            }
+            write_test_markers(
+                f,
+                &self.content,
+                &self.matches,
+                0,
+                self.config.colors.text,
+                &self.config,
+            )?;
         } else {
             write_content_with_highlights(
                 f,
-                raw,
+                &self.content,
                 &self.matches,
-                cut,
-                self.config.colors.text,
+                0,
+                &text_colors(&self.syntax, &self.config),
                 filter,
                 &self.config,
             )?;
         }
-
-        if self.context_offset.is_some() && self.config.with_colors {
+        if self.is_context && self.config.with_colors {
             write!(f, "{}", style::RESET)?;
         }
+        if self.config.hyperlink.is_some() {
+            Hyperlink::end(f)?;
+        }
         if self.new_line {
             writeln!(f)?;
         }
@@ -488,26 +768,137 @@ impl Entry for LineDisplay {
         false
     }
     fn filter_text(&self) -> String {
-        let raw: &[u8] = if self.config.search.trim {
-            let n = self
-                .content
-                .iter()
-                .take_while(|&&b| b.is_ascii_whitespace())
-                .count();
-            &self.content[n..]
-        } else {
-            &self.content
-        };
-        String::from_utf8_lossy(raw).into_owned()
+        String::from_utf8_lossy(&self.content).into_owned()
+    }
+    fn is_wrap(&self) -> bool {
+        true
     }
 }
 
-impl Display for LineDisplay {
+impl Display for WrapDisplay {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.render(f, "")
     }
 }
 
+fn line_num_label(line_num: usize, context_offset: Option<isize>) -> String {
+    context_offset.map_or_else(|| format!("{}: ", line_num), |o| format!("{:+}: ", o))
+}
+
+fn flat_line_num(line_num: usize, is_context: bool, config: &Config) -> String {
+    let sep = if is_context { '-' } else { ':' };
+    if config.search.line_number {
+        format!("{sep}{}{sep}", line_num)
+    } else {
+        sep.to_string()
+    }
+}
+
+fn location_line_num(line_num: usize) -> String {
+    format!(":{}: ", line_num)
+}
+
+fn line_num_width(line: &Line, config: &Config) -> usize {
+    if config.search.line_number {
+        line_num_label(line.line_num, line.context_offset).len()
+    } else {
+        0
+    }
+}
+
+fn wrap_width(config: &Config) -> usize {
+    let width = TERM_WIDTH.load(Ordering::SeqCst) as usize;
+    if config.select || config.menu {
+        width.saturating_sub(width::width(&config.chars.selected_indicator))
+    } else {
+        width
+    }
+}
+
+fn line_ranges(content: &[u8], used_width: usize, config: &Config) -> Vec<(usize, usize)> {
+    if !config.search.wrap {
+        return vec![(0, content.len())];
+    }
+    let skip = if config.search.trim {
+        content
+            .iter()
+            .take_while(|b| b.is_ascii_whitespace())
+            .count()
+    } else {
+        0
+    };
+    let width = wrap_width(config).saturating_sub(used_width);
+    let width = config.search.max_length.map_or(width, |m| m.min(width));
+    wrap_ranges(content, skip, width)
+}
+
+fn wrap_expanded<'a>(line: &'a Line, config: &Config) -> (Cow<'a, [u8]>, Cow<'a, [Match]>) {
+    match config
+        .search
+        .wrap
+        .then(|| width::expand_tabs(&line.content, &line.matches, 0, config.tab_width))
+        .flatten()
+    {
+        Some((c, m)) => (Cow::Owned(c), Cow::Owned(m)),
+        None => (Cow::Borrowed(&line.content), Cow::Borrowed(&line.matches)),
+    }
+}
+
+fn push_wraps(lines: &mut Vec<Box<dyn Entry>>, line: WrapDisplay, ranges: &[(usize, usize)]) {
+    for &(start, end) in ranges {
+        lines.push(Box::new(WrapDisplay {
+            prefix: line.prefix.clone(),
+            content: line.content[start..end].to_vec(),
+            path: line.path.clone(),
+            matches: clip_matches(&line.matches, start, end),
+            syntax: syntax::clip(&line.syntax, start, end),
+            config: Arc::clone(&line.config),
+            ..line
+        }));
+    }
+}
+
+fn wrap_ranges(content: &[u8], skip: usize, width: usize) -> Vec<(usize, usize)> {
+    let width = width.max(1);
+    let bounds = width::boundaries(content);
+    let mut ranges = Vec::new();
+    let (mut start, mut col) = (0, width::column_before(&bounds, skip));
+    loop {
+        let (mut end, mut end_col) = width::last_within(&bounds, col + width);
+        if end <= start {
+            (end, end_col) = width::first_from(&bounds, col + 1);
+        } else if end < content.len()
+            && let Some(&(b, c)) = bounds
+                .iter()
+                .rev()
+                .skip_while(|&&(b, _)| b > end)
+                .take_while(|&&(b, _)| b > start.max(skip))
+                .find(|&&(b, _)| content[b - 1].is_ascii_whitespace())
+        {
+            (end, end_col) = (b, c);
+        }
+        ranges.push((start, end));
+        if end >= content.len() {
+            return ranges;
+        }
+        (start, col) = (end, end_col);
+    }
+}
+
+fn clip_matches(matches: &[Match], start: usize, end: usize) -> Vec<Match> {
+    matches
+        .iter()
+        .filter(|m| m.start < end && m.end > start)
+        .map(|m| {
+            Match::new(
+                m.regexp_id,
+                m.start.max(start) - start,
+                m.end.min(end) - start,
+            )
+        })
+        .collect()
+}
+
 struct LongBranchDisplay {
     prefix: Vec<PrefixComponent>,
     files: Vec<PathDisplay>,
@@ -522,15 +913,7 @@ impl Entry for LongBranchDisplay {
             write!(f, "{}", style::RESET)?;
         }
         for (i, file) in self.files.iter().enumerate() {
-            write_path(
-                f,
-                &file.name,
-                file.color,
-                file.linked.as_deref(),
-                file.count,
-                filter,
-                &self.config,
-            )?;
+            write_path(f, file, filter)?;
             if i + 1 != self.files.len() {
                 f.write_str(style::LONG_BRANCH_FILE_SEPARATOR)?;
             }
@@ -572,19 +955,116 @@ struct OverviewDisplay {
     files: usize,
     lines: usize,
     count: usize,
+    patterns: Vec<Totals>,
+    groups: HashMap<String, Totals>,
     new_line: bool,
     config: Arc<Config>,
 }
 
-impl Entry for OverviewDisplay {
-    fn render(&self, f: &mut fmt::Formatter, filter: &str) -> fmt::Result {
-        if self.config.with_colors || self.config.with_bold {
-            write!(f, "{}", style::RESET)?;
-        }
-        let text = self.filter_text();
-        write_content_with_highlights(f, text.as_bytes(), &[], 0, None, filter, &self.config)?;
-        if self.new_line {
-            writeln!(f)?;
+impl OverviewDisplay {
+    fn add_file(&mut self, file: &File) {
+        let lines = file
+            .lines
+            .iter()
+            .filter(|l| l.context_offset.is_none())
+            .count();
+        self.lines += lines;
+        self.count += file.count();
+
+        if !self.patterns.is_empty() {
+            let mut in_file = vec![false; self.patterns.len()];
+            for line in file.lines.iter().filter(|l| l.context_offset.is_none()) {
+                let mut in_line = vec![false; self.patterns.len()];
+                for m in &line.matches {
+                    self.patterns[m.regexp_id].matches += 1;
+                    in_line[m.regexp_id] = true;
+                }
+                for (id, _) in in_line.iter().enumerate().filter(|(_, hit)| **hit) {
+                    self.patterns[id].lines += 1;
+                    in_file[id] = true;
+                }
+            }
+            for (id, _) in in_file.iter().enumerate().filter(|(_, hit)| **hit) {
+                self.patterns[id].files += 1;
+            }
+        }
+
+        if let Some(by) = self.config.search.overview_by {
+            let group = self
+                .groups
+                .entry(group_name(file, by, &self.config))
+                .or_default();
+            group.files += 1;
+            group.lines += lines;
+            group.matches += file.count();
+        }
+    }
+
+    fn rows(&self) -> Vec<Box<dyn Entry>> {
+        let mut rows: Vec<Box<dyn Entry>> = Vec::new();
+        for (id, totals) in self.patterns.iter().enumerate() {
+            rows.push(Box::new(OverviewRowDisplay {
+                label: self.config.search.regexps[id].clone(),
+                regexp_id: Some(id),
+                totals: *totals,
+                new_line: self.new_line,
+                config: Arc::clone(&self.config),
+            }));
+        }
+        let mut groups: Vec<(&String, &Totals)> = self.groups.iter().collect();
+        groups.sort_by(|(a_name, a), (b_name, b)| {
+            b.matches
+                .cmp(&a.matches)
+                .then_with(|| b.files.cmp(&a.files))
+                .then_with(|| a_name.cmp(b_name))
+        });
+        for (name, totals) in groups {
+            rows.push(Box::new(OverviewRowDisplay {
+                label: name.clone(),
+                regexp_id: None,
+                totals: *totals,
+                new_line: self.new_line,
+                config: Arc::clone(&self.config),
+            }));
+        }
+        rows
+    }
+}
+
+fn counts_matches(config: &Config) -> bool {
+    let search = &config.search;
+    !search.files && !search.name_regexp && !search.lists_paths()
+}
+
+fn group_name(file: &File, by: OverviewBy, config: &Config) -> String {
+    match by {
+        OverviewBy::Ext => file.path.extension().map_or_else(
+            || "(none)".to_string(),
+            |e| format!(".{}", e.to_string_lossy()),
+        ),
+        OverviewBy::Dir => {
+            let relative = file
+                .path
+                .strip_prefix(&config.search.path)
+                .unwrap_or(&file.path);
+            let mut components = relative.components();
+            match (components.next(), components.next()) {
+                (Some(top), Some(_)) => top.as_os_str().to_string_lossy().into_owned(),
+                _ => ".".to_string(),
+            }
+        }
+    }
+}
+
+impl Entry for OverviewDisplay {
+    fn render(&self, f: &mut fmt::Formatter, filter: &str) -> fmt::Result {
+        if self.config.with_colors || self.config.with_bold {
+            write!(f, "{}", style::RESET)?;
+        }
+        let text = self.filter_text();
+        write_content_with_highlights(f, text.as_bytes(), &[], 0, &[], filter, &self.config)?;
+        if self.new_line {
+            writeln!(f)?;
         }
         Ok(())
     }
@@ -599,7 +1079,7 @@ impl Entry for OverviewDisplay {
     }
     fn filter_text(&self) -> String {
         let mut s = String::new();
-        if !self.config.search.files {
+        if counts_matches(&self.config) {
             s.push_str(&format!(
                 "found {} matches in {} lines across ",
                 self.count, self.lines
@@ -623,6 +1103,444 @@ impl Display for OverviewDisplay {
     }
 }
 
+struct OverviewRowDisplay {
+    label: String,
+    regexp_id: Option<usize>,
+    totals: Totals,
+    new_line: bool,
+    config: Arc<Config>,
+}
+
+impl Entry for OverviewRowDisplay {
+    fn render(&self, f: &mut fmt::Formatter, filter: &str) -> fmt::Result {
+        if self.config.with_colors || self.config.with_bold {
+            write!(f, "{}", style::RESET)?;
+        }
+        let text = self.filter_text();
+        let matches: Vec<Match> = self
+            .regexp_id
+            .map(|id| Match::new(id, 0, self.label.len()))
+            .into_iter()
+            .collect();
+        if cfg!(feature = "test") {
+            write_test_markers(f, text.as_bytes(), &matches, 0, None, &self.config)?;
+        } else {
+            write_content_with_highlights(
+                f,
+                text.as_bytes(),
+                &matches,
+                0,
+                &[],
+                filter,
+                &self.config,
+            )?;
+        }
+        if self.new_line {
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+    fn open_info(&self) -> Result<OpenInfo<'_>, Message> {
+        Err(mes!("can't open stats"))
+    }
+    fn depth(&self) -> usize {
+        0
+    }
+    fn is_path(&self) -> bool {
+        false
+    }
+    fn filter_text(&self) -> String {
+        let mut s = format!("{}: ", self.label);
+        if counts_matches(&self.config) {
+            s.push_str(&format!(
+                "{} in {} across ",
+                plural(self.totals.matches, "match", "matches"),
+                plural(self.totals.lines, "line", "lines")
+            ));
+        }
+        s.push_str(&plural(self.totals.files, "file", "files"));
+        s
+    }
+}
+
+impl Display for OverviewRowDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, "")
+    }
+}
+
+struct TallyDisplay {
+    value: String,
+    regexp_id: usize,
+    count: usize,
+    count_width: usize,
+    new_line: bool,
+    config: Arc<Config>,
+}
+
+impl Entry for TallyDisplay {
+    fn render(&self, f: &mut fmt::Formatter, filter: &str) -> fmt::Result {
+        if self.config.with_colors || self.config.with_bold {
+            write!(f, "{}", style::RESET)?;
+        }
+        write!(f, "{:>w$} ", self.count, w = self.count_width)?;
+        if cfg!(feature = "test") {
+            write!(
+                f,
+                "[m{}s]{}[m{}e]",
+                self.regexp_id,
+                style::match_substring(&self.value, self.regexp_id, &self.config),
+                self.regexp_id
+            )?;
+        } else {
+            write_content_with_highlights(
+                f,
+                self.value.as_bytes(),
+                &[Match::new(self.regexp_id, 0, self.value.len())],
+                0,
+                &[],
+                filter,
+                &self.config,
+            )?;
+        }
+        if self.new_line {
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+    fn open_info(&self) -> Result<OpenInfo<'_>, Message> {
+        Err(mes!("can't open a tally, unfold it to choose a location"))
+    }
+    fn depth(&self) -> usize {
+        0
+    }
+    fn is_path(&self) -> bool {
+        true
+    }
+    fn filter_text(&self) -> String {
+        self.value.clone()
+    }
+}
+
+impl Display for TallyDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, "")
+    }
+}
+
+struct LocationDisplay {
+    prefix: Vec<PrefixComponent>,
+    icon: Option<String>,
+    name: String,
+    color: Color,
+    path: PathBuf,
+    content: Vec<u8>,
+    matches: Vec<Match>,
+    line_num: usize,
+    new_line: bool,
+    config: Arc<Config>,
+}
+
+impl Entry for LocationDisplay {
+    fn render(&self, f: &mut fmt::Formatter, filter: &str) -> fmt::Result {
+        write_prefix(f, &self.prefix, &self.config)?;
+        if self.config.with_colors || self.config.with_bold {
+            write!(f, "{}", style::RESET)?;
+        }
+        write_icon(f, self.icon.as_deref(), self.color, &self.config)?;
+        if cfg!(feature = "test") {
+            write!(f, "[ps]{}[pe]", self.name)?;
+        } else {
+            write_content_with_highlights(
+                f,
+                self.name.as_bytes(),
+                &[],
+                0,
+                &[(0, self.color)],
+                filter,
+                &self.config,
+            )?;
+        }
+        let line_num = location_line_num(self.line_num);
+        write!(
+            f,
+            "{}",
+            style::style_with(
+                line_num.as_str(),
+                self.config.colors.line_number,
+                &self.config
+            )
+        )?;
+        write_line_content(
+            f,
+            LineText {
+                content: &self.content,
+                matches: &self.matches,
+                syntax: &[],
+            },
+            false,
+            self.config.branch_len * self.prefix.len()
+                + icon_width(self.icon.as_deref())
+                + width::width(&self.name)
+                + line_num.len(),
+            filter,
+            &self.config,
+        )?;
+        if self.new_line {
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+    fn open_info(&self) -> Result<OpenInfo<'_>, Message> {
+        Ok(OpenInfo {
+            path: &self.path,
+            line: Some(self.line_num),
+        })
+    }
+    fn depth(&self) -> usize {
+        self.prefix.len()
+    }
+    fn is_path(&self) -> bool {
+        false
+    }
+    fn filter_text(&self) -> String {
+        format!(
+            "{} {}",
+            self.name,
+            trimmed_text(&self.content, &self.config)
+        )
+    }
+}
+
+impl Display for LocationDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, "")
+    }
+}
+
+struct FlatLineDisplay {
+    icon: Option<String>,
+    name: String,
+    color: Color,
+    path: PathBuf,
+    content: Vec<u8>,
+    matches: Vec<Match>,
+    line_num: usize,
+    is_context: bool,
+    new_line: bool,
+    config: Arc<Config>,
+}
+
+impl Entry for FlatLineDisplay {
+    fn render(&self, f: &mut fmt::Formatter, filter: &str) -> fmt::Result {
+        write_icon(f, self.icon.as_deref(), self.color, &self.config)?;
+        if let Some(link) = &self.config.hyperlink {
+            link.start(f, &self.path, Some(self.line_num))?;
+        }
+        if cfg!(feature = "test") {
+            write!(f, "[ps]{}[pe]", self.name)?;
+        } else {
+            write_content_with_highlights(
+                f,
+                self.name.as_bytes(),
+                &[],
+                0,
+                &[(0, self.color)],
+                filter,
+                &self.config,
+            )?;
+        }
+        if self.config.with_colors || self.config.with_bold {
+            write!(f, "{}", style::RESET)?;
+        }
+        let line_num = flat_line_num(self.line_num, self.is_context, &self.config);
+        if self.config.search.line_number {
+            write!(
+                f,
+                "{}",
+                style::style_with(
+                    line_num.as_str(),
+                    self.config.colors.line_number,
+                    &self.config
+                )
+            )?;
+        } else {
+            f.write_str(&line_num)?;
+        }
+        write_line_content(
+            f,
+            LineText {
+                content: &self.content,
+                matches: &self.matches,
+                syntax: &[],
+            },
+            self.is_context,
+            icon_width(self.icon.as_deref()) + width::width(&self.name) + line_num.len(),
+            filter,
+            &self.config,
+        )?;
+        if self.config.hyperlink.is_some() {
+            Hyperlink::end(f)?;
+        }
+        if self.new_line {
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+    fn open_info(&self) -> Result<OpenInfo<'_>, Message> {
+        Ok(OpenInfo {
+            path: &self.path,
+            line: Some(self.line_num),
+        })
+    }
+    fn depth(&self) -> usize {
+        0
+    }
+    fn is_path(&self) -> bool {
+        false
+    }
+    fn filter_text(&self) -> String {
+        format!(
+            "{} {}",
+            self.name,
+            trimmed_text(&self.content, &self.config)
+        )
+    }
+}
+
+impl Display for FlatLineDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render(f, "")
+    }
+}
+
+fn relative_name(path: &Path, config: &Config) -> String {
+    path.strip_prefix(&config.search.path)
+        .ok()
+        .filter(|p| !p.as_os_str().is_empty())
+        .or_else(|| path.file_name().map(Path::new))
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+struct Tally<'a> {
+    count: usize,
+    locations: Vec<(&'a File, &'a Line)>,
+}
+
+fn tally_to_lines(
+    result: &Matches,
+    lines: &mut Vec<Box<dyn Entry>>,
+    overview: &mut Option<&mut Box<OverviewDisplay>>,
+    config: &Arc<Config>,
+) {
+    let files: Vec<&File> = match result {
+        Matches::Dir(dirs) => {
+            if let Some(o) = overview {
+                o.dirs += dirs.len();
+                o.files += dirs.iter().map(|d| d.files.len()).sum::<usize>();
+            }
+            dirs.iter().flat_map(|d| d.files.iter()).collect()
+        }
+        Matches::File(file) => vec![file],
+    };
+
+    let mut tallies: HashMap<(usize, Vec<u8>), Tally> = HashMap::new();
+    for file in &files {
+        if let Some(o) = overview {
+            o.add_file(file);
+        }
+        for line in &file.lines {
+            for m in line.matches.iter().filter(|m| m.start < m.end) {
+                let entry = tallies
+                    .entry((m.regexp_id, line.content[m.start..m.end].to_vec()))
+                    .or_insert_with(|| Tally {
+                        count: 0,
+                        locations: Vec::new(),
+                    });
+                entry.count += 1;
+                if entry
+                    .locations
+                    .last()
+                    .is_none_or(|(_, l)| !std::ptr::eq(*l, line))
+                {
+                    entry.locations.push((file, line));
+                }
+            }
+        }
+    }
+
+    let mut tallies: Vec<((usize, Vec<u8>), Tally)> = tallies.into_iter().collect();
+    tallies.sort_by(|((a_id, a_value), a), ((b_id, b_value), b)| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a_value.cmp(b_value))
+            .then_with(|| a_id.cmp(b_id))
+    });
+
+    let count_width = tallies
+        .first()
+        .map_or(0, |(_, t)| t.count.to_string().len());
+    let new_line = !config.select && !config.menu;
+    for ((regexp_id, value), Tally { count, locations }) in tallies {
+        lines.push(Box::new(TallyDisplay {
+            value: String::from_utf8_lossy(&value).into_owned(),
+            regexp_id,
+            count,
+            count_width,
+            new_line,
+            config: Arc::clone(config),
+        }));
+        if !config.menu && !config.select {
+            continue;
+        }
+        for (i, (file, line)) in locations.iter().enumerate() {
+            let (prefix, wrap_prefix) = if i + 1 != locations.len() {
+                (PrefixComponent::MatchWithNext, PrefixComponent::SpacerVert)
+            } else {
+                (PrefixComponent::MatchNoNext, PrefixComponent::Spacer)
+            };
+            let name = relative_name(&file.path, config);
+            let (content, matches) = wrap_expanded(line, config);
+            let icon = file_icon(&file.path, config);
+            let indent = icon_width(icon.as_deref())
+                + width::width(&name)
+                + location_line_num(line.line_num).len();
+            let ranges = line_ranges(&content, config.branch_len + indent, config);
+            let (start, end) = ranges[0];
+            lines.push(Box::new(LocationDisplay {
+                prefix: vec![prefix],
+                icon,
+                name,
+                color: path_color(&file.path, false, config),
+                path: file.path.clone(),
+                content: content[start..end].to_vec(),
+                matches: clip_matches(&matches, start, end),
+                line_num: line.line_num,
+                new_line,
+                config: Arc::clone(config),
+            }));
+            push_wraps(
+                lines,
+                WrapDisplay {
+                    prefix: vec![wrap_prefix],
+                    indent,
+                    content: content.into_owned(),
+                    path: file.path.clone(),
+                    matches: matches.into_owned(),
+                    syntax: Spans::new(),
+                    line_num: line.line_num,
+                    is_context: false,
+                    new_line,
+                    config: Arc::clone(config),
+                },
+                &ranges[1..],
+            );
+        }
+    }
+}
+
 fn path_name(path: &Path) -> Result<&str, Message> {
     let err = || {
         mes!(
@@ -633,6 +1551,47 @@ fn path_name(path: &Path) -> Result<&str, Message> {
     path.file_name().ok_or_else(err)?.to_str().ok_or_else(err)
 }
 
+fn plural(n: usize, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
+
+fn dir_summary(totals: &Totals, all: &Totals, config: &Config) -> String {
+    let mut s = plural(totals.files, "file", "files");
+    if !config.search.regexps.is_empty() && !config.search.name_regexp {
+        if config.search.totals {
+            s.push_str(", ");
+            s.push_str(&plural(totals.lines, "line", "lines"));
+        }
+        s.push_str(", ");
+        s.push_str(&plural(totals.matches, "match", "matches"));
+        if config.search.totals && all.matches > 0 {
+            s.push_str(&format!(
+                ", {}%",
+                (totals.matches * 100 + all.matches / 2) / all.matches
+            ));
+        }
+    }
+    s
+}
+
+fn chain_name(chain: &[&Directory]) -> Result<(String, Vec<Match>), Message> {
+    let mut name = String::new();
+    let mut matches = Vec::new();
+    for dir in chain {
+        if !name.is_empty() {
+            name.push(std::path::MAIN_SEPARATOR);
+        }
+        let offset = name.len();
+        name.push_str(path_name(&dir.path)?);
+        matches.extend(
+            dir.name_matches
+                .iter()
+                .map(|m| Match::new(m.regexp_id, m.start + offset, m.end + offset)),
+        );
+    }
+    Ok((name, matches))
+}
+
 fn with_push(prefix: &[PrefixComponent], item: PrefixComponent) -> Vec<PrefixComponent> {
     let mut v = prefix.to_vec();
     v.push(item);
@@ -649,30 +1608,54 @@ impl Directory {
         overview: &mut Option<&mut Box<OverviewDisplay>>,
         config: &Arc<Config>,
     ) -> Result<(), Message> {
-        let children = &self.children;
-        let files = &self.files;
+        if config.search.flat {
+            if let Some(o) = overview {
+                o.dirs += 1;
+            }
+            for child in &self.children {
+                dirs[*child].to_lines(lines, &[], &[], dirs, overview, config)?;
+            }
+            for file in &self.files {
+                file.to_lines(lines, &[], false, overview, config)?;
+            }
+            return Ok(());
+        }
+        let chain = self.compact_chain(dirs, config);
+        let dir = chain[chain.len() - 1];
+        let children = &dir.children;
+        let files = &dir.files;
         let flen = files.len();
         let clen = children.len();
-        if clen > 0 || flen > 0 {
-            lines.push(Box::new(PathDisplay::new(
-                Some(cur_prefix.to_vec()),
-                &self.path,
-                &self.linked,
-                self.children.len() + self.files.len(),
-                !config.select && !config.menu,
-                true,
-                Arc::clone(config),
-            )?));
+        let (name, name_matches) = chain_name(&chain)?;
+        if clen > 0 || flen > 0 || !name_matches.is_empty() || config.search.dirs {
+            lines.push(Box::new(
+                PathDisplay::new(
+                    Some(cur_prefix.to_vec()),
+                    &dir.path,
+                    &dir.linked,
+                    clen + flen,
+                    !config.select && !config.menu,
+                    true,
+                    Arc::clone(config),
+                )?
+                .with_name(name)
+                .with_matches(&name_matches)
+                .with_summary(
+                    (config.search.dirs || config.search.totals)
+                        .then(|| dir_summary(&dir.totals, &dirs[0].totals, config)),
+                ),
+            ));
         }
 
         if let Some(o) = overview {
-            o.dirs += 1;
+            o.dirs += chain.len();
             o.files += files.len();
         }
 
+        let files_shown = flen > 0 && !config.search.dirs;
         for (i, child_id) in children.iter().enumerate() {
-            let dir = &dirs[*child_id];
-            let (cur_prefix, new_child_prefix) = if i + 1 != clen || flen > 0 {
+            let child = &dirs[*child_id];
+            let (cur_prefix, new_child_prefix) = if i + 1 != clen || files_shown {
                 (
                     with_push(child_prefix, PrefixComponent::MatchWithNext),
                     with_push(child_prefix, PrefixComponent::SpacerVert),
@@ -683,7 +1666,7 @@ impl Directory {
                     with_push(child_prefix, PrefixComponent::Spacer),
                 )
             };
-            dir.to_lines(
+            child.to_lines(
                 lines,
                 &cur_prefix,
                 &new_child_prefix,
@@ -692,9 +1675,16 @@ impl Directory {
                 config,
             )?;
         }
-        if !files.is_empty() {
+        if config.search.dirs {
+            if let Some(o) = overview {
+                files.iter().for_each(|file| o.add_file(file));
+            }
+        } else if !files.is_empty() {
             if config.search.branch_each > 1 {
-                self.long_branch_files_to_lines(lines, child_prefix, config)?;
+                if let Some(o) = overview {
+                    files.iter().for_each(|file| o.add_file(file));
+                }
+                dir.long_branch_files_to_lines(lines, child_prefix, config)?;
             } else {
                 for (i, file) in files.iter().enumerate() {
                     file.to_lines(lines, child_prefix, i + 1 != flen, overview, config)?;
@@ -704,6 +1694,21 @@ impl Directory {
         Ok(())
     }
 
+    fn compact_chain<'a>(&'a self, dirs: &'a [Directory], config: &Config) -> Vec<&'a Directory> {
+        let mut chain = vec![self];
+        let mut dir = self;
+        while config.search.compact_dirs
+            && !std::ptr::eq(self, &dirs[0])
+            && dir.files.is_empty()
+            && dir.children.len() == 1
+            && dir.linked.is_none()
+        {
+            dir = &dirs[dir.children[0]];
+            chain.push(dir);
+        }
+        chain
+    }
+
     fn long_branch_files_to_lines(
         &self,
         lines: &mut Vec<Box<dyn Entry>>,
@@ -734,6 +1739,7 @@ impl Directory {
                             false,
                             Arc::clone(config),
                         )
+                        .map(|p| p.with_matches(&f.name_matches))
                     })
                     .collect::<Result<Vec<PathDisplay>, Message>>()?,
                 new_line: !config.select && !config.menu,
@@ -754,12 +1760,10 @@ impl File {
         config: &Arc<Config>,
     ) -> Result<(), Message> {
         if let Some(o) = overview {
-            o.lines += self
-                .lines
-                .iter()
-                .filter(|l| l.context_offset.is_none())
-                .count();
-            o.count += self.lines.iter().map(|l| l.matches.len()).sum::<usize>();
+            o.add_file(self);
+        }
+        if config.search.flat {
+            return self.flat_to_lines(lines, config);
         }
         let (cur_p, line_p) = if config.is_dir {
             if parent_has_next {
@@ -777,38 +1781,152 @@ impl File {
             (prefix.to_vec(), prefix.to_vec())
         };
 
-        lines.push(Box::new(PathDisplay::new(
-            Some(cur_p),
-            &self.path,
-            &self.linked,
-            self.count(),
-            !config.select && !config.menu,
-            false,
-            Arc::clone(config),
-        )?));
+        lines.push(Box::new(
+            PathDisplay::new(
+                Some(cur_p),
+                &self.path,
+                &self.linked,
+                self.count(),
+                !config.select && !config.menu,
+                false,
+                Arc::clone(config),
+            )?
+            .with_matches(&self.name_matches)
+            .with_meta(self.meta),
+        ));
 
-        if !config.search.files {
+        if !config.search.files && !config.search.dirs {
+            let mut highlighter = (config.search.syntax && config.with_colors)
+                .then(|| Highlighter::new(&self.path, &config.syntax_theme));
             for (i, line) in self.lines.iter().enumerate() {
-                let prefix = if i + 1 != self.lines.len() {
-                    with_push(&line_p, PrefixComponent::MatchWithNext)
+                let (prefix, wrap_prefix) = if i + 1 != self.lines.len() {
+                    (
+                        with_push(&line_p, PrefixComponent::MatchWithNext),
+                        with_push(&line_p, PrefixComponent::SpacerVert),
+                    )
                 } else {
-                    with_push(&line_p, PrefixComponent::MatchNoNext)
+                    (
+                        with_push(&line_p, PrefixComponent::MatchNoNext),
+                        with_push(&line_p, PrefixComponent::Spacer),
+                    )
+                };
+                let expanded = (config.search.wrap || highlighter.is_some())
+                    .then(|| width::expand_tabs(&line.content, &line.matches, 0, config.tab_width))
+                    .flatten();
+                let (content, matches) = match &expanded {
+                    Some((c, m)) => (c.as_slice(), m.as_slice()),
+                    None => (line.content.as_slice(), line.matches.as_slice()),
                 };
+                let spans = highlighter
+                    .as_mut()
+                    .map(|h| h.line(content))
+                    .unwrap_or_default();
+                let indent = line_num_width(line, config);
+                let ranges =
+                    line_ranges(content, config.branch_len * prefix.len() + indent, config);
+                let (start, end) = ranges[0];
                 lines.push(Box::new(LineDisplay {
                     prefix,
-                    content: line.content.clone(),
+                    content: content[start..end].to_vec(),
                     path: self.path.clone(),
-                    matches: line.matches.clone(),
+                    matches: clip_matches(matches, start, end),
+                    syntax: syntax::clip(&spans, start, end),
                     line_num: line.line_num,
                     context_offset: line.context_offset,
                     new_line: !config.select && !config.menu,
                     config: Arc::clone(config),
                 }));
+                push_wraps(
+                    lines,
+                    WrapDisplay {
+                        prefix: wrap_prefix,
+                        indent,
+                        content: content.to_vec(),
+                        path: self.path.clone(),
+                        matches: matches.to_vec(),
+                        syntax: spans,
+                        line_num: line.line_num,
+                        is_context: line.context_offset.is_some(),
+                        new_line: !config.select && !config.menu,
+                        config: Arc::clone(config),
+                    },
+                    &ranges[1..],
+                );
             }
         }
         Ok(())
     }
 
+    fn flat_to_lines(
+        &self,
+        lines: &mut Vec<Box<dyn Entry>>,
+        config: &Arc<Config>,
+    ) -> Result<(), Message> {
+        let name = relative_name(&self.path, config);
+        if config.search.files || self.lines.is_empty() {
+            let offset = name.len() - path_name(&self.path)?.len();
+            let name_matches: Vec<Match> = self
+                .name_matches
+                .iter()
+                .map(|m| Match::new(m.regexp_id, m.start + offset, m.end + offset))
+                .collect();
+            lines.push(Box::new(
+                PathDisplay::new(
+                    Some(Vec::new()),
+                    &self.path,
+                    &self.linked,
+                    self.count(),
+                    !config.select && !config.menu,
+                    false,
+                    Arc::clone(config),
+                )?
+                .with_name(name)
+                .with_matches(&name_matches)
+                .with_meta(self.meta),
+            ));
+            return Ok(());
+        }
+        for line in &self.lines {
+            let is_context = line.context_offset.is_some();
+            let (content, matches) = wrap_expanded(line, config);
+            let icon = file_icon(&self.path, config);
+            let indent = icon_width(icon.as_deref())
+                + width::width(&name)
+                + flat_line_num(line.line_num, is_context, config).len();
+            let ranges = line_ranges(&content, indent, config);
+            let (start, end) = ranges[0];
+            lines.push(Box::new(FlatLineDisplay {
+                icon,
+                name: name.clone(),
+                color: path_color(&self.path, false, config),
+                path: self.path.clone(),
+                content: content[start..end].to_vec(),
+                matches: clip_matches(&matches, start, end),
+                line_num: line.line_num,
+                is_context,
+                new_line: !config.select && !config.menu,
+                config: Arc::clone(config),
+            }));
+            push_wraps(
+                lines,
+                WrapDisplay {
+                    prefix: Vec::new(),
+                    indent,
+                    content: content.into_owned(),
+                    path: self.path.clone(),
+                    matches: matches.into_owned(),
+                    syntax: Spans::new(),
+                    line_num: line.line_num,
+                    is_context,
+                    new_line: !config.select && !config.menu,
+                    config: Arc::clone(config),
+                },
+                &ranges[1..],
+            );
+        }
+        Ok(())
+    }
+
     fn count(&self) -> usize {
         self.lines.iter().map(|l| l.matches.len()).sum()
     }
@@ -826,11 +1944,20 @@ pub fn matches_to_display_lines(
             files: usize::from(!config.is_dir),
             lines: 0,
             count: 0,
+            patterns: if config.search.regexps.len() > 1 && counts_matches(&config) {
+                vec![Totals::default(); config.search.regexps.len()]
+            } else {
+                Vec::new()
+            },
+            groups: HashMap::new(),
             new_line: !config.select && !config.menu,
             config: Arc::clone(&config),
         })
         .map(Box::new);
     match &result {
+        _ if config.search.tally.is_some() => {
+            tally_to_lines(result, &mut lines, &mut overview.as_mut(), &config);
+        }
         Matches::Dir(dirs) => {
             dirs.first().unwrap().to_lines(
                 &mut lines,
@@ -846,10 +1973,12 @@ pub fn matches_to_display_lines(
         }
     }
     if let Some(o) = overview.take() {
+        let rows = o.rows();
         if config.search.overview_only {
-            return Ok(vec![o]);
+            lines.clear();
         }
         lines.push(o);
+        lines.extend(rows);
     }
     Ok(lines)
 }
@@ -861,6 +1990,100 @@ pub fn write_results(out: &mut Term, lines: &[Box<dyn Entry>]) -> io::Result<()>
     Ok(())
 }
 
+struct TemplateFields<'a> {
+    file: &'a File,
+    relpath: &'a Path,
+    count: usize,
+    line: Option<&'a Line>,
+    m: Option<&'a Match>,
+}
+
+fn write_template(
+    out: &mut impl Write,
+    template: &Template,
+    fields: &TemplateFields,
+) -> io::Result<()> {
+    for part in &template.0 {
+        match part {
+            TemplatePart::Text(t) => out.write_all(t.as_bytes())?,
+            TemplatePart::Field(Placeholder::Path) => {
+                out.write_all(fields.file.path.as_os_str().as_encoded_bytes())?
+            }
+            TemplatePart::Field(Placeholder::Relpath) => {
+                out.write_all(fields.relpath.as_os_str().as_encoded_bytes())?
+            }
+            TemplatePart::Field(Placeholder::Depth) => {
+                write!(out, "{}", fields.relpath.components().count())?
+            }
+            TemplatePart::Field(Placeholder::Count) => write!(out, "{}", fields.count)?,
+            TemplatePart::Field(Placeholder::Line) => {
+                if let Some(line) = fields.line {
+                    write!(out, "{}", line.line_num)?;
+                }
+            }
+            TemplatePart::Field(Placeholder::Text) => {
+                if let Some(line) = fields.line {
+                    out.write_all(&line.content)?;
+                }
+            }
+            TemplatePart::Field(Placeholder::Col) => {
+                if let Some(m) = fields.m {
+                    write!(out, "{}", m.start + 1)?;
+                }
+            }
+            TemplatePart::Field(Placeholder::Match) => {
+                if let (Some(line), Some(m)) = (fields.line, fields.m) {
+                    out.write_all(&line.content[m.start..m.end])?;
+                }
+            }
+            TemplatePart::Field(Placeholder::PatternId) => {
+                if let Some(m) = fields.m {
+                    write!(out, "{}", m.regexp_id)?;
+                }
+            }
+        }
+    }
+    out.write_all(b"\n")
+}
+
+pub fn write_templates(
+    out: &mut impl Write,
+    matches: Option<&Matches>,
+    config: &Config,
+) -> io::Result<()> {
+    let root = if config.is_dir {
+        config.search.path.as_path()
+    } else {
+        config.search.path.parent().unwrap_or(&config.search.path)
+    };
+    let templates = &config.templates;
+    for file in matches.map(collect_files).unwrap_or_default() {
+        let mut fields = TemplateFields {
+            file,
+            relpath: file.path.strip_prefix(root).unwrap_or(&file.path),
+            count: file.count(),
+            line: None,
+            m: None,
+        };
+        if let Some(t) = &templates.file {
+            write_template(out, t, &fields)?;
+        }
+        for line in &file.lines {
+            fields.line = Some(line);
+            if let Some(t) = &templates.line {
+                fields.m = line.matches.iter().find(|m| m.start < m.end);
+                write_template(out, t, &fields)?;
+            } else if let Some(t) = &templates.each_match {
+                for m in line.matches.iter().filter(|m| m.start < m.end) {
+                    fields.m = Some(m);
+                    write_template(out, t, &fields)?;
+                }
+            }
+        }
+    }
+    out.flush()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -879,6 +2102,50 @@ mod tests {
         let _ = filter_matches_in("İ", "i");
     }
 
+    #[test]
+    fn test_truncate_window() {
+        let raw = b"0123456789abcdefghijklmnopqrstuvwxyz";
+        assert_eq!(truncate_window(raw, &[Match::new(0, 2, 4)], 0, 10), (0, 9));
+        assert_eq!(truncate_window(raw, &[], 0, 10), (0, 9));
+        assert_ne!(truncate_window(raw, &[Match::new(0, 8, 10)], 0, 10).0, 0);
+        assert_eq!(
+            truncate_window(raw, &[Match::new(0, 20, 22)], 0, 10),
+            (17, 25)
+        );
+        assert_eq!(
+            truncate_window(raw, &[Match::new(0, 34, 36)], 0, 10),
+            (27, 36)
+        );
+        assert_eq!(
+            truncate_window(raw, &[Match::new(0, 22, 24)], 2, 10),
+            (17, 25)
+        );
+        assert_eq!(
+            truncate_window(b"short", &[Match::new(0, 1, 2)], 0, 10),
+            (0, 5)
+        );
+    }
+
+    #[test]
+    fn test_wrap_ranges() {
+        assert_eq!(wrap_ranges(b"abcdefg", 0, 3), vec![(0, 3), (3, 6), (6, 7)]);
+        assert_eq!(wrap_ranges(b"  abcdef", 2, 3), vec![(0, 5), (5, 8)]);
+        assert_eq!(wrap_ranges("aé".as_bytes(), 0, 2), vec![(0, 3)]);
+        assert_eq!(
+            wrap_ranges("日本語".as_bytes(), 0, 3),
+            vec![(0, 3), (3, 6), (6, 9)]
+        );
+        assert_eq!(wrap_ranges(b"", 0, 3), vec![(0, 0)]);
+        assert_eq!(wrap_ranges(b"ab cd efgh", 0, 6), vec![(0, 6), (6, 10)]);
+        assert_eq!(wrap_ranges(b"  ab cdef", 2, 4), vec![(0, 5), (5, 9)]);
+    }
+
+    #[test]
+    fn test_clip_matches() {
+        let matches = [Match::new(0, 1, 4), Match::new(1, 5, 6)];
+        assert_eq!(clip_matches(&matches, 2, 5), vec![Match::new(0, 0, 2)]);
+    }
+
     #[test]
     fn test_path_name() {
         let mut path = Path::new("/path/to/file.txt");
@@ -4,6 +4,7 @@ use std::{
     env, fs,
     io::Write,
     path::{Path, PathBuf},
+    time::{Duration, UNIX_EPOCH},
 };
 
 static TEST_DIR: &str = "treegrep_tests";
@@ -23,6 +24,15 @@ impl Dir {
         Dir { path }
     }
 
+    pub fn with_sub(name: &str, top: &[u8], sub: &[u8]) -> Self {
+        let dir = Dir::new(name);
+        dir.create_file_fill(&PathBuf::from("top_file"), top);
+        let sub_dir = PathBuf::from("sub");
+        dir.add_child(&sub_dir);
+        dir.create_file_fill(&sub_dir.join("sub_file"), sub);
+        dir
+    }
+
     pub fn add_child(&self, name: &PathBuf) {
         let p = self.path.join(name);
         if !p.exists() {
@@ -38,6 +48,14 @@ impl Dir {
         file.sync_all().expect("failed to sync file");
     }
 
+    pub fn set_modified(&self, file_name: &PathBuf, secs: u64) {
+        fs::File::options()
+            .write(true)
+            .open(self.path.join(file_name))
+            .and_then(|f| f.set_modified(UNIX_EPOCH + Duration::from_secs(secs)))
+            .expect("failed to set modification time");
+    }
+
     pub fn link_dir(&self, target_dir: &Path, link_name: &str) {
         let link_path = self.path.join(link_name);
 
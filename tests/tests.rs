@@ -261,16 +261,12 @@ fn overlapping() {
 #[test]
 fn overview() {
     let tar_dir: PathBuf = target_dir();
-    let dir = Dir::new("overview");
-    dir.create_file_fill(
-        &PathBuf::from("top_file"),
+    let dir = Dir::with_sub(
+        "overview",
         b"some text\nmore text\nother line",
+        b"some text here too",
     );
 
-    let sub = PathBuf::from("sub");
-    dir.add_child(&sub);
-    dir.create_file_fill(&sub.join("sub_file"), b"some text here too");
-
     let result = get_output(&dir.path, "text --overview");
     assert_pass(&tar_dir.join("overview_dir"), result);
 
@@ -281,16 +277,12 @@ fn overview() {
 #[test]
 fn overview_only() {
     let tar_dir: PathBuf = target_dir();
-    let dir = Dir::new("summary");
-    dir.create_file_fill(
-        &PathBuf::from("top_file"),
+    let dir = Dir::with_sub(
+        "summary",
         b"some text\nmore text\nother line",
+        b"some text here too",
     );
 
-    let sub = PathBuf::from("sub");
-    dir.add_child(&sub);
-    dir.create_file_fill(&sub.join("sub_file"), b"some text here too");
-
     let result = get_output(&dir.path, "text --overview-only");
     assert_pass(&tar_dir.join("summary_dir"), result);
 
@@ -298,6 +290,367 @@ fn overview_only() {
     assert_pass(&tar_dir.join("summary_file"), result);
 }
 
+#[test]
+fn overview_breakdown() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("overview_breakdown");
+    dir.create_file_fill(&PathBuf::from("top.rs"), b"alpha beta\nalpha");
+
+    let sub = PathBuf::from("sub");
+    dir.add_child(&sub);
+    dir.create_file_fill(&sub.join("one.rs"), b"beta beta");
+    dir.create_file_fill(&sub.join("two.md"), b"alpha\nnothing");
+
+    let result = get_output(&dir.path, "-e alpha -e beta --overview-only");
+    assert_pass(&tar_dir.join("overview_patterns"), result);
+
+    let result = get_output(&dir.path, "alpha --overview-only --overview-by=ext");
+    assert_pass(&tar_dir.join("overview_by_ext"), result);
+
+    let result = get_output(
+        &dir.path,
+        "-e alpha -e beta --overview-only --overview-by=dir",
+    );
+    assert_pass(&tar_dir.join("overview_by_dir"), result);
+
+    let result = get_output(
+        &dir.path,
+        "alpha --files --branch-each 2 --overview --overview-by=ext",
+    );
+    assert_pass(&tar_dir.join("overview_branch_each"), result);
+}
+
+#[test]
+fn tally() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("tally");
+    dir.create_file_fill(
+        &PathBuf::from("top_file"),
+        b"use FEATURE_A;\nif FEATURE_B and FEATURE_A\nflag(name=\"x\") flag(name=\"y\")",
+    );
+
+    let sub = PathBuf::from("sub");
+    dir.add_child(&sub);
+    dir.create_file_fill(&sub.join("sub_file"), b"FEATURE_A again\nflag(name=\"x\")");
+
+    let result = get_output(&dir.path, r"FEATURE_\w+ --tally");
+    assert_pass(&tar_dir.join("tally"), result);
+
+    let result = get_output(&dir.path, r#"flag\(name="(\w+)"\) --tally=1"#);
+    assert_pass(&tar_dir.join("tally_group"), result);
+
+    let result = get_output(&dir.path, r"-e ^FEATURE_\w+ -e FEATURE_A$ --tally");
+    assert_pass(&tar_dir.join("tally_patterns"), result);
+}
+
+#[test]
+fn name_regexp() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("name_regexp");
+    dir.create_file_fill(&PathBuf::from("readme"), b"conf in contents only");
+
+    let conf = PathBuf::from("conf_dir");
+    dir.add_child(&conf);
+    dir.create_file_fill(&conf.join("settings.toml"), b"value = 1");
+    dir.create_file_fill(&conf.join("notes"), b"conf");
+
+    let empty = PathBuf::from("empty_conf");
+    dir.add_child(&empty);
+
+    let result = get_output(&dir.path, "-N -e conf_ -e toml -c");
+    assert_pass(&tar_dir.join("name_regexp"), result);
+}
+
+#[test]
+fn dirs() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("dirs");
+    dir.create_file_fill(&PathBuf::from("top_file"), b"needle\nneedle needle");
+
+    let first = PathBuf::from("first");
+    dir.add_child(&first);
+    dir.create_file_fill(&first.join("one"), b"needle");
+    dir.create_file_fill(&first.join("two"), b"nothing here");
+
+    let second = first.join("second");
+    dir.add_child(&second);
+    dir.create_file_fill(&second.join("three"), b"a needle and a needle");
+
+    let result = get_output(&dir.path, "needle --dirs");
+    assert_pass(&tar_dir.join("dirs"), result);
+
+    let result = get_output(&dir.path, "--dirs");
+    assert_pass(&tar_dir.join("dirs_no_expression"), result);
+
+    let result = get_output(&dir.path, "needle --count --totals");
+    assert_pass(&tar_dir.join("dirs_totals"), result);
+}
+
+#[test]
+fn compact_dirs() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("compact_dirs");
+    dir.create_file_fill(&PathBuf::from("top_file"), b"needle");
+
+    let chain = PathBuf::from("src").join("main").join("acme");
+    dir.add_child(&PathBuf::from("src"));
+    dir.add_child(&PathBuf::from("src").join("main"));
+    dir.add_child(&chain);
+    dir.create_file_fill(&chain.join("App"), b"a needle");
+
+    let result = get_output(&dir.path, "needle --compact-dirs");
+    assert_pass(&tar_dir.join("compact_dirs"), result);
+
+    let result = get_output(&dir.path, "-N -e ai -e cm --compact-dirs --dirs");
+    assert_pass(&tar_dir.join("compact_dirs_names"), result);
+}
+
+#[test]
+fn json() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("json");
+    dir.create_file_fill(
+        &PathBuf::from("top_file"),
+        b"before\nsay \"hello\" world\nafter\n",
+    );
+
+    let result = get_rooted_output(&dir.path, &dir.path, r"-e hel+o -e world --json -A1");
+    assert_pass(&tar_dir.join("json"), result);
+
+    let sub = PathBuf::from("sub");
+    dir.add_child(&sub);
+    dir.create_file_fill(&sub.join("sub_file"), b"hello\nhello hello");
+
+    let result = get_rooted_output(&dir.path, &dir.path, "hello --output-format=tree-json");
+    assert_pass(&tar_dir.join("tree_json"), result);
+
+    let result = get_rooted_output(&dir.path, &dir.path.join("top_file"), "z* --json");
+    assert_pass(&tar_dir.join("json_empty_matches"), result);
+
+    let result = get_rooted_output(
+        &dir.path,
+        &dir.path.join("top_file"),
+        "hel+o --output-format=rg-json -B1",
+    );
+    assert_pass(&tar_dir.join("rg_json"), result);
+}
+
+#[test]
+fn vimgrep() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::with_sub("vimgrep", b"one\nhello and hello\nthree", b"  hello\n");
+
+    let result = get_rooted_output(&dir.path, &dir.path, "hello --vimgrep");
+    assert_pass(&tar_dir.join("vimgrep"), result);
+
+    let quickfix = dir.path.join("quickfix");
+    let result = get_output(
+        &dir.path.join("top_file"),
+        &format!("hello --quickfix-file={}", quickfix.to_string_lossy()),
+    );
+    assert_pass(&tar_dir.join("vimgrep_tree"), result);
+    let result = replace_root(&dir.path, std::fs::read(&quickfix).unwrap());
+    assert_pass(&tar_dir.join("vimgrep_quickfix"), result);
+}
+
+#[test]
+fn sarif() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("sarif");
+    dir.create_file_fill(&PathBuf::from("top_file"), b"one\ndbg!(x) // TODO\n");
+    let sub = PathBuf::from("sub dir");
+    dir.add_child(&sub);
+    dir.create_file_fill(&sub.join("sub_file"), "\u{e9}TODO".as_bytes());
+
+    let result = get_rooted_output(
+        &dir.path,
+        &dir.path,
+        r"-e dbg! --rule-name=no_dbg -e TODO --output-format=sarif",
+    );
+    assert_pass(&tar_dir.join("sarif"), result);
+}
+
+#[test]
+fn html() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::with_sub("html", b"before\n<b>hello</b> & hello\n", b"hello");
+
+    let result = get_rooted_output(&dir.path, &dir.path, "hello --output-format=html -n -B1 -c");
+    assert_pass(&tar_dir.join("html"), result);
+}
+
+#[test]
+fn markdown() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("markdown");
+    dir.create_file_fill(&PathBuf::from("main.rs"), b"fn main() {\n    todo!()\n}\n");
+    let sub = PathBuf::from("sub");
+    dir.add_child(&sub);
+    dir.create_file_fill(&sub.join("notes"), b"```\ntodo: fences\n```");
+
+    let result = get_output(&dir.path, "todo --output-format=markdown -C1");
+    assert_pass(&tar_dir.join("markdown"), result);
+}
+
+#[test]
+fn format() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::with_sub("format", b"a hello b hello\nno\n", b"{hello}");
+
+    let result = get_output(
+        &dir.path,
+        r"-e hel+o -e b --format={relpath}:{line}:{col}:{pattern_id}:{match}\t{depth} --format-file=#{{{relpath}}}:{count}",
+    );
+    assert_pass(&tar_dir.join("format"), result);
+
+    let result = get_output(&dir.path, r"hello --format-line={line}|{text}|{match} -A1");
+    assert_pass(&tar_dir.join("format_line"), result);
+}
+
+#[test]
+fn null() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("null");
+    dir.create_file_fill(&PathBuf::from("top file"), b"hello");
+    let sub = PathBuf::from("sub");
+    dir.add_child(&sub);
+    dir.create_file_fill(&sub.join("sub_file"), b"hello");
+    dir.create_file_fill(&sub.join("other"), b"bye");
+
+    let result = get_rooted_output(&dir.path, &dir.path, "hello -0");
+    assert_pass(&tar_dir.join("null"), result);
+
+    let result = get_rooted_output(&dir.path, &dir.path, "--files --null");
+    assert_pass(&tar_dir.join("null_files"), result);
+}
+
+#[test]
+fn flat() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::with_sub("flat", b"before\nhello there\nafter", b"hello\nbye\nhello");
+
+    let result = get_output(&dir.path, "hello --flat");
+    assert_pass(&tar_dir.join("flat"), result);
+
+    let result = get_output(&dir.path, "hello --flat -n -B1");
+    assert_pass(&tar_dir.join("flat_context"), result);
+
+    let result = get_output(&dir.path, "hello --flat --files");
+    assert_pass(&tar_dir.join("flat_files"), result);
+}
+
+#[test]
+fn max_length_window() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("max_length_window");
+    let file = PathBuf::from("minified");
+    dir.create_file_fill(
+        &file,
+        b"{\"a\":1,\"b\":2,\"c\":3,\"needle\":4,\"d\":5,\"e\":6,\"f\":7}\nneedle at the start of a long line\n",
+    );
+
+    let result = get_output(&dir.path.join(&file), "needle --max-length=16");
+    assert_pass(&tar_dir.join("max_length_window"), result);
+}
+
+#[test]
+fn wrap() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("wrap");
+    let file = PathBuf::from("long_lines");
+    dir.create_file_fill(
+        &file,
+        b"short needle line\nthe quick brown fox jumps over the lazy dog while the needle waits patiently for the thread to pass through its eye\nlast line\n",
+    );
+
+    let result = get_output(&dir.path, "needle --wrap -n -A1");
+    assert_pass(&tar_dir.join("wrap"), result);
+
+    let result = get_output(&dir.path, "needle --wrap -n --max-length=20");
+    assert_pass(&tar_dir.join("wrap_max_length"), result);
+
+    let result = get_output(&dir.path, "needle --wrap --flat -n --max-length=30");
+    assert_pass(&tar_dir.join("wrap_flat"), result);
+}
+
+#[test]
+fn wide_chars() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("wide_chars");
+    let file = PathBuf::from("japanese");
+    dir.create_file_fill(
+        &file,
+        "製品の説明文にはneedleという単語が含まれています\n\tkey\tneedle\n".as_bytes(),
+    );
+
+    let result = get_output(
+        &dir.path.join(&file),
+        "needle --max-length=20 --tab-width=8",
+    );
+    assert_pass(&tar_dir.join("wide_chars"), result);
+}
+
+#[test]
+fn theme_file() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("theme_file");
+    let theme = PathBuf::from("ascii.theme");
+    dir.create_file_fill(
+        &theme,
+        b"base = light\nvertical = |\nhorizontal = -\ntee = +\nbottom_left = `\n",
+    );
+    dir.create_file_fill(&PathBuf::from("a"), b"match\nother match\n");
+    dir.create_file_fill(&PathBuf::from("b"), b"match\n");
+
+    let result = get_output(
+        &dir.path,
+        &format!(
+            "match --glob=!*.theme --theme={}",
+            dir.path.join(&theme).display()
+        ),
+    );
+    assert_pass(&tar_dir.join("theme_file"), result);
+}
+
+#[test]
+fn icons() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("icons");
+    dir.create_file_fill(&PathBuf::from("main.rs"), b"match\n");
+    dir.create_file_fill(&PathBuf::from("notes"), b"match\n");
+    dir.create_file_fill(&PathBuf::from("Cargo.toml"), b"match\n");
+    let rs = PathBuf::from("rs");
+    dir.add_child(&rs);
+    dir.create_file_fill(&rs.join("dir"), b"match\n");
+
+    let result = get_output(
+        &dir.path,
+        "match --icons --icon=ext:rs=R --icon=dir=D --icon=file=F",
+    );
+    assert_pass(&tar_dir.join("icons"), result);
+
+    let result = get_output(
+        &dir.path.join("main.rs"),
+        "match --icons --icon=ext:rs=R --flat --wrap --max-length=3",
+    );
+    assert_pass(&tar_dir.join("icons_flat"), result);
+}
+
+#[test]
+fn long() {
+    let tar_dir: PathBuf = target_dir();
+    let dir = Dir::new("long");
+    let small = PathBuf::from("small");
+    let large = PathBuf::from("large");
+    dir.create_file_fill(&small, b"match\n");
+    dir.create_file_fill(&large, &[b"match\n".as_slice(); 512].concat());
+    dir.set_modified(&small, 1_709_210_096);
+    dir.set_modified(&large, 0);
+
+    let result = get_output(&dir.path, "match --long --files");
+    assert_pass(&tar_dir.join("long"), result);
+}
+
 #[test]
 fn context() {
     let tar_dir: PathBuf = target_dir();
@@ -447,6 +800,24 @@ fn menu_filter() {
     assert_pass(&tar_dir.join("menu_filter"), result);
 }
 
+#[cfg(unix)]
+#[test]
+fn menu_wrap() {
+    let tar_dir = target_dir();
+    let dir = Dir::new("menu_wrap");
+    dir.create_file_fill(
+        &PathBuf::from("long_lines"),
+        b"the needle waits patiently for the thread to pass through its eye\nneedle two\n",
+    );
+
+    let result = tmux::get_menu_output(
+        &dir.path,
+        "needle --wrap --max-length=20",
+        &["/", "the thread", "Enter", "j", "j", "j", "j"],
+    );
+    assert_pass(&tar_dir.join("menu_wrap"), result);
+}
+
 #[cfg(unix)]
 #[test]
 fn menu_custom_keys() {
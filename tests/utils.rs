@@ -96,6 +96,17 @@ pub fn get_output(path: &Path, args: &str) -> Vec<u8> {
     stdout
 }
 
+pub fn replace_root(root: &Path, output: Vec<u8>) -> Vec<u8> {
+    String::from_utf8(output)
+        .unwrap()
+        .replace(&*root.to_string_lossy(), "DIR")
+        .into_bytes()
+}
+
+pub fn get_rooted_output(root: &Path, path: &Path, args: &str) -> Vec<u8> {
+    replace_root(root, get_output(path, args))
+}
+
 pub fn target_dir() -> PathBuf {
     let p = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .join("tests")